@@ -11,7 +11,7 @@ import {
   resolveMarket,
   withdrawFees,
   closeMarket,
-  fetchMarket,
+  fetchAuthorityMarkets,
   ParsedBetMarket,
 } from "@/lib/solana";
 import { HostStreamPanel } from "@/components/stream/HostStreamPanel";
@@ -62,6 +62,9 @@ export default function StudioPage() {
   }, [passwordInput, storageKey, STUDIO_PASSWORD]);
 
   const [market, setMarket] = useState<ParsedBetMarket | null>(null);
+  // Every open market of this authority; `selectedCycle` picks the one shown (newest by default).
+  const [markets, setMarkets] = useState<ParsedBetMarket[]>([]);
+  const [selectedCycle, setSelectedCycle] = useState<number | null>(null);
   const [actionLoading, setActionLoading] = useState<string | null>(null);
   const [lastSignature, setLastSignature] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
@@ -115,16 +118,17 @@ export default function StudioPage() {
 
   const refresh = useCallback(async () => {
     try {
-      if (!publicKey) { setMarket(null); return null; }
-      const m = await fetchMarket(wallet, undefined);
-      const next = m?.data || null;
+      if (!publicKey) { setMarkets([]); setMarket(null); return null; }
+      const all = (await fetchAuthorityMarkets(publicKey)).map(m => m.data);
+      const next = all.find(m => m.cycle === selectedCycle) ?? all[all.length - 1] ?? null;
+      setMarkets(all);
       setMarket(next);
       return next;
     } catch (e) {
       console.error(e);
       return null;
     }
-  }, [publicKey, wallet]);
+  }, [publicKey, selectedCycle]);
 
   useEffect(() => { refresh(); }, [refresh]);
 
@@ -398,6 +402,7 @@ export default function StudioPage() {
   const initMarket = async () => {
     const result = await run('init', () => initializeMarket(wallet, { title, labelYes, labelNo, feeBps: feeBpsNumber() }));
     if (isInitOutcome(result)) {
+      if (typeof result.cycle === 'number') setSelectedCycle(result.cycle);
       await publishMarketState({ market: result.market, cycle: result.cycle }, result.txSig);
     }
     return result;
@@ -462,6 +467,7 @@ export default function StudioPage() {
       feeBps: 20,
     }));
     if (isInitOutcome(result)) {
+      if (typeof result.cycle === 'number') setSelectedCycle(result.cycle);
       await publishMarketState({ market: result.market, cycle: result.cycle }, result.txSig);
     }
   };
@@ -474,6 +480,7 @@ export default function StudioPage() {
     const defaultNo = "No";
     const result = await run('init', () => initializeMarket(wallet, { title: question, labelYes: defaultYes, labelNo: defaultNo, feeBps: 20 }));
     if (isInitOutcome(result)) {
+      if (typeof result.cycle === 'number') setSelectedCycle(result.cycle);
       await publishMarketState({ market: result.market, cycle: result.cycle }, result.txSig);
     }
     if (result !== false && publicKey) {
@@ -491,7 +498,9 @@ export default function StudioPage() {
   };
 
   const closeCurrentMarket = async () => {
-    const outcome = await run('close', () => closeMarket(wallet));
+    if (!market) return;
+    const cycle = market.cycle;
+    const outcome = await run('close', () => closeMarket(wallet, cycle));
     if (outcome !== false && hasTxSig(outcome)) {
       await publishMarketState(null, outcome.txSig);
     }
@@ -584,6 +593,19 @@ export default function StudioPage() {
                 </span>
               )}
             </div>
+            {markets.length > 1 && (
+              <select
+                className="w-full rounded-md border border-white/10 bg-black/30 px-3 py-2 text-[12px] text-white focus:outline-none focus:ring-2 focus:ring-[var(--accent)]/40"
+                value={market?.cycle ?? ''}
+                onChange={(e) => setSelectedCycle(Number(e.target.value))}
+              >
+                {markets.map(m => (
+                  <option key={m.cycle} value={m.cycle} className="bg-black text-white">
+                    {(m.title || 'Untitled Market') + (m.resolved ? ' (resolved)' : m.frozen ? ' (frozen)' : '')}
+                  </option>
+                ))}
+              </select>
+            )}
             {market ? (
               <div className="space-y-4">
                 <div>
@@ -635,17 +657,17 @@ export default function StudioPage() {
                       )}
                       <button
                         disabled={market.frozen || actionLoading==='freeze'}
-                        onClick={() => run('freeze', () => freezeMarket(wallet))}
+                        onClick={() => run('freeze', () => freezeMarket(wallet, market.cycle))}
                         className="btn btn-sm btn-warning"
                       >
                         {actionLoading==='freeze' ? '...' : 'Freeze Poll'}
                       </button>
-                      <button disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_yes', () => resolveMarket(wallet, 0))} className="btn btn-sm btn-success">{actionLoading==='resolve_yes'?"...":`Set: ${market.labelYes || 'YES'}`}</button>
-                      <button disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_no', () => resolveMarket(wallet, 1))} className="btn btn-sm btn-danger">{actionLoading==='resolve_no'?"...":`Set: ${market.labelNo || 'NO'}`}</button>
+                      <button disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_yes', () => resolveMarket(wallet, market.cycle, 0))} className="btn btn-sm btn-success">{actionLoading==='resolve_yes'?"...":`Set: ${market.labelYes || 'YES'}`}</button>
+                      <button disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_no', () => resolveMarket(wallet, market.cycle, 1))} className="btn btn-sm btn-danger">{actionLoading==='resolve_no'?"...":`Set: ${market.labelNo || 'NO'}`}</button>
                     </>
                   )}
                   {market.feesAccrued > 0 && (
-                    <button disabled={actionLoading==='withdraw'} onClick={() => run('withdraw', () => withdrawFees(wallet))} className="btn btn-sm btn-warning">{actionLoading==='withdraw'?'...':'Collect Host Fees'}</button>
+                    <button disabled={actionLoading==='withdraw'} onClick={() => run('withdraw', () => withdrawFees(wallet, market.cycle))} className="btn btn-sm btn-warning">{actionLoading==='withdraw'?'...':'Collect Host Fees'}</button>
                   )}
                   {market.resolved && market.feesAccrued === 0 && (
                     <button disabled={actionLoading==='close'} onClick={closeCurrentMarket} className="btn btn-sm btn-outline">{actionLoading==='close'?'...':'Archive Poll'}</button>
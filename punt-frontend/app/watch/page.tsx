@@ -10,9 +10,8 @@ import {
   withdrawFees,
   closeMarket,
   closeTicket,
-  fetchMarket,
+  fetchAuthorityMarkets,
   fetchTicket,
-  fetchMarketPublic,
   fetchMarketTicketCountPublic,
   lamportsToSol,
   ParsedBetMarket,
@@ -87,6 +86,9 @@ export default function WatchPage() {
   const realtimeListenerIdsRef = useRef<number[]>([]);
   const lastBetCountFetchRef = useRef(0);
   const [market, setMarket] = useState<ParsedBetMarket | null>(null);
+  // Every open market of the watched authority; `selectedCycle` picks the one shown (newest by default).
+  const [markets, setMarkets] = useState<ParsedBetMarket[]>([]);
+  const [selectedCycle, setSelectedCycle] = useState<number | null>(null);
   const [ticket, setTicket] = useState<ParsedBetTicket | null>(null);
   const [betAmount, setBetAmount] = useState<string>("0.001");
   const [sideChoice, setSideChoice] = useState<0 | 1 | null>(null);
@@ -127,6 +129,10 @@ export default function WatchPage() {
     lastBetCountFetchRef.current = 0;
   }, [selectedAuthorityBase58, walletBase58]);
 
+  useEffect(() => {
+    setSelectedCycle(null);
+  }, [selectedAuthorityBase58]);
+
   useEffect(() => {
     const key = market ? `${market.authority}:${market.cycle}` : null;
     if (key !== prevMarketKeyRef.current) {
@@ -163,57 +169,21 @@ export default function WatchPage() {
     let countAuthority: PublicKey | null = null;
 
     try {
-      if (!publicKey) {
-        if (selectedAuthority) {
-          const m = await fetchMarketPublic(selectedAuthority);
-          if (m?.data) {
-            nextMarket = m.data;
-            nextMarketKey = m.pubkey?.toBase58?.() ?? null;
-            countAuthority = selectedAuthority;
-          } else {
-            nextBetCount = null;
-          }
-        } else {
-          nextBetCount = null;
-        }
-      } else if (selectedAuthority && selectedAuthority.toBase58() !== publicKey.toBase58()) {
-        const m = await fetchMarketPublic(selectedAuthority);
-        if (m?.data) {
-          nextMarket = m.data;
-          nextMarketKey = m.pubkey?.toBase58?.() ?? null;
-          const t = await fetchTicket(wallet, selectedAuthority);
-          if (t?.data) {
-            nextTicket = t.data;
-            nextTicketKey = t.pubkey?.toBase58?.() ?? null;
-          } else {
-            nextTicket = null;
-            nextTicketKey = null;
-          }
-          countAuthority = selectedAuthority;
-        } else {
-          nextTicket = null;
-          nextTicketKey = null;
-          nextBetCount = null;
-        }
-      } else if (publicKey) {
-        const m = await fetchMarket(wallet, undefined);
-        if (m?.data) {
-          nextMarket = m.data;
-          nextMarketKey = m.pubkey?.toBase58?.() ?? null;
-          const t = await fetchTicket(wallet, undefined);
-          if (t?.data) {
-            nextTicket = t.data;
-            nextTicketKey = t.pubkey?.toBase58?.() ?? null;
-          } else {
-            nextTicket = null;
-            nextTicketKey = null;
-          }
-          countAuthority = selectedAuthority ?? publicKey;
-        } else {
-          nextTicket = null;
-          nextTicketKey = null;
-          nextBetCount = null;
+      const authority = selectedAuthority ?? publicKey;
+      const all = authority ? await fetchAuthorityMarkets(authority) : [];
+      setMarkets(all.map(entry => entry.data));
+      const m = all.find(entry => entry.data.cycle === selectedCycle) ?? all[all.length - 1] ?? null;
+      if (authority && m) {
+        nextMarket = m.data;
+        nextMarketKey = m.pubkey.toBase58();
+        countAuthority = authority;
+        const t = publicKey ? await fetchTicket(wallet, m.data.cycle, authority) : null;
+        if (t?.data) {
+          nextTicket = t.data;
+          nextTicketKey = t.pubkey?.toBase58?.() ?? null;
         }
+      } else {
+        nextBetCount = null;
       }
     } catch (e) {
       console.error(e);
@@ -226,7 +196,7 @@ export default function WatchPage() {
       const shouldFetchCount = now - lastBetCountFetchRef.current > 1_000 || nextBetCount === null;
       if (shouldFetchCount) {
         try {
-          nextBetCount = await fetchMarketTicketCountPublic(countAuthority);
+          nextBetCount = await fetchMarketTicketCountPublic(countAuthority, nextMarket.cycle);
           lastBetCountFetchRef.current = now;
         } catch (err) {
           console.warn('[watch] failed to refresh bet count', err);
@@ -242,7 +212,7 @@ export default function WatchPage() {
       setBetCount(nextBetCount);
     }
     return { market: nextMarket, ticket: nextTicket };
-  }, [publicKey, wallet, selectedAuthority, betCount]);
+  }, [publicKey, wallet, selectedAuthority, selectedCycle, betCount]);
 
   useEffect(() => {
     let cancelled = false;
@@ -575,9 +545,10 @@ export default function WatchPage() {
     const authorityCandidate = selectedAuthority ?? publicKey ?? fallbackAuthority;
     if (!authorityCandidate) return;
     const authorityPk = authorityCandidate;
+    const marketId = market.cycle;
     async function tick() {
       try {
-        const count = await fetchMarketTicketCountPublic(authorityPk);
+        const count = await fetchMarketTicketCountPublic(authorityPk, marketId);
         if (cancelled) return;
         if (!market || market.resolved) {
           setBetCount(null);
@@ -813,7 +784,7 @@ export default function WatchPage() {
       (async () => {
         try {
           const success = await run('claim_auto', async () => {
-            const res = await claimWinnings(wallet, authorityArg);
+            const res = await claimWinnings(wallet, market.cycle, authorityArg);
             if (res && 'txSig' in res && typeof res.txSig === 'string') {
               setAutoOutcome({ kind: 'won', amountSol: payoutSol, signature: res.txSig });
             }
@@ -844,7 +815,7 @@ export default function WatchPage() {
     (async () => {
       try {
         const success = await run('close_ticket_auto', async () => {
-          const res = await closeTicket(wallet, authorityArg);
+          const res = await closeTicket(wallet, market.cycle, authorityArg);
           if (isLoser && res && 'txSig' in res && typeof res.txSig === 'string') {
             setAutoOutcome({ kind: 'lost', amountSol: lostAmountSol, signature: res.txSig });
           }
@@ -896,7 +867,8 @@ export default function WatchPage() {
   // Streamer selection via sidebar removed; URL parameter is the only source now.
 
   const handleBet = async (side: 0 | 1) => {
-    if (market?.frozen) {
+    if (!market) return false;
+    if (market.frozen) {
       addToast({ type: "error", message: "Poll frozen – no new bets allowed." });
       return false;
     }
@@ -905,6 +877,7 @@ export default function WatchPage() {
       const betResult = await bet(wallet, { 
         side, 
         amountLamports: solToLamports(betAmount), 
+        cycle: market.cycle,
         marketAuthority: selectedAuthority || undefined 
       });
       
@@ -956,8 +929,8 @@ export default function WatchPage() {
   };
 
   const submitBet = async () => {
-    if (ticket) {
-      if (market?.frozen) {
+    if (ticket && market) {
+      if (market.frozen) {
         addToast({ type: "error", message: "Poll frozen – no new bets allowed." });
         return;
       }
@@ -965,6 +938,7 @@ export default function WatchPage() {
         const betResult = await bet(wallet, {
           side: ticket.side as 0 | 1,
           amountLamports: solToLamports(betAmount),
+          cycle: market.cycle,
           marketAuthority: selectedAuthority || undefined,
         });
         
@@ -1140,6 +1114,19 @@ export default function WatchPage() {
             {!pollHidden && (
               <h2 className="mt-1 text-lg font-semibold leading-tight text-white break-words">{market?.title || 'Untitled Market'}</h2>
             )}
+            {!pollHidden && markets.length > 1 && (
+              <select
+                value={market?.cycle ?? ''}
+                onChange={e => setSelectedCycle(Number(e.target.value))}
+                className="w-full rounded-md border border-white/10 bg-black/30 px-3 py-2 text-[12px] text-white focus:outline-none focus:ring-2 focus:ring-[var(--accent)]/40"
+              >
+                {markets.map(m => (
+                  <option key={m.cycle} value={m.cycle} className="bg-black text-white">
+                    {(m.title || 'Untitled Market') + (m.resolved ? ' (resolved)' : m.frozen ? ' (frozen)' : '')}
+                  </option>
+                ))}
+              </select>
+            )}
             {pollHidden ? null : isInitialLoad ? (
               <div className="space-y-4 animate-pulse">
                 <div className="h-4 rounded bg-white/10" />
@@ -1367,7 +1354,7 @@ export default function WatchPage() {
                       <button
                         type="button"
                         disabled={market.frozen || !!actionLoading}
-                        onClick={() => run('freeze', () => freezeMarket(wallet))}
+                        onClick={() => run('freeze', () => freezeMarket(wallet, market.cycle))}
                         className="btn btn-sm border border-amber-300 text-amber-200 hover:bg-amber-300/10"
                       >
                         {freezeLoading ? '...' : 'Freeze Poll'}
@@ -1382,21 +1369,21 @@ export default function WatchPage() {
                           Poll frozen. Select the winning side below.
                         </div>
                       )}
-                      <button type="button" disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_yes', () => resolveMarket(wallet, 0))} className="btn btn-sm bg-[var(--accent)] hover:bg-[var(--accent)]/90 text-[var(--accent-contrast)] border-transparent">
+                      <button type="button" disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_yes', () => resolveMarket(wallet, market.cycle, 0))} className="btn btn-sm bg-[var(--accent)] hover:bg-[var(--accent)]/90 text-[var(--accent-contrast)] border-transparent">
                         {actionLoading === 'resolve_yes' ? '...' : `Set: ${market.labelYes || 'YES'}`}
                       </button>
-                      <button type="button" disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_no', () => resolveMarket(wallet, 1))} className="btn btn-sm bg-[var(--accent)] hover:bg-[var(--accent)]/90 text-[var(--accent-contrast)] border-transparent">
+                      <button type="button" disabled={!market.frozen || !!actionLoading} onClick={() => run('resolve_no', () => resolveMarket(wallet, market.cycle, 1))} className="btn btn-sm bg-[var(--accent)] hover:bg-[var(--accent)]/90 text-[var(--accent-contrast)] border-transparent">
                         {actionLoading === 'resolve_no' ? '...' : `Set: ${market.labelNo || 'NO'}`}
                       </button>
                     </>
                   )}
                   {userIsAuthority && market.feesAccrued > 0 && (
-                    <button type="button" disabled={!!actionLoading} onClick={() => run('withdraw', () => withdrawFees(wallet))} className="btn btn-sm bg-[var(--accent)] hover:bg-[var(--accent)]/90 text-[var(--accent-contrast)] border-transparent">
+                    <button type="button" disabled={!!actionLoading} onClick={() => run('withdraw', () => withdrawFees(wallet, market.cycle))} className="btn btn-sm bg-[var(--accent)] hover:bg-[var(--accent)]/90 text-[var(--accent-contrast)] border-transparent">
                       {actionLoading ? '...' : 'Collect Host Fees'}
                     </button>
                   )}
                   {userIsAuthority && market.resolved && market.feesAccrued === 0 && (
-                    <button type="button" disabled={!!actionLoading} onClick={() => run('close', () => closeMarket(wallet))} className="btn btn-sm border border-[var(--accent)] text-[var(--accent)] hover:bg-[var(--accent)]/10">
+                    <button type="button" disabled={!!actionLoading} onClick={() => run('close', () => closeMarket(wallet, market.cycle))} className="btn btn-sm border border-[var(--accent)] text-[var(--accent)] hover:bg-[var(--accent)]/10">
                       {actionLoading ? '...' : 'Archive Poll'}
                     </button>
                   )}
@@ -1455,7 +1442,7 @@ export default function WatchPage() {
                     {autoClaimFailed && (
                       <button
                         disabled={actionLoading === 'claim' || actionLoading === 'claim_auto' || autoProcessing === 'claim'}
-                        onClick={() => run('claim', () => claimWinnings(wallet, selectedAuthority || undefined))}
+                        onClick={() => run('claim', () => claimWinnings(wallet, market.cycle, selectedAuthority || undefined))}
                         className="btn w-full btn-sm bg-[var(--accent)] hover:bg-[var(--accent)]/90 text-[var(--accent-contrast)] border-transparent"
                       >
                         {actionLoading === 'claim' ? '...' : 'Claim Payout Manually'}
@@ -1481,7 +1468,7 @@ export default function WatchPage() {
                     {autoCloseFailed && (
                       <button
                         disabled={actionLoading === 'close_ticket' || actionLoading === 'close_ticket_auto' || autoProcessing === 'close'}
-                        onClick={() => run('close_ticket', () => closeTicket(wallet, selectedAuthority || undefined))}
+                        onClick={() => run('close_ticket', () => closeTicket(wallet, market.cycle, selectedAuthority || undefined))}
                         className="btn w-full btn-sm border border-[var(--accent)] text-[var(--accent)] hover:bg-[var(--accent)]/10"
                       >
                         {actionLoading === 'close_ticket' ? '...' : 'Clear Position Manually'}
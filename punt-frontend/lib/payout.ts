@@ -1,27 +1,71 @@
 import type { ParsedBetMarket, ParsedBetTicket } from "./solana";
 
+// Mirrors of `settled_gross`, `net_payout` and `referral_share` in the punt-math crate
+// (punt-program/crates/punt-math/src/lib.rs). Keep them in sync: both test suites run the vectors
+// in punt-program/crates/punt-math/tests/vectors.json. `null` stands for the Rust `None`.
+
+const BPS_DENOMINATOR = BigInt(10_000);
+const U64_MAX = BigInt("18446744073709551615");
+const SIDE_VOID = 2;
+const NO_WINNER_REFUND = 1;
+
+const toU64 = (x: bigint): bigint | null => (x >= BigInt(0) && x <= U64_MAX ? x : null);
+
+/** Pro-rata gross payout for a winning stake, rounded down. */
+export function grossPayout(stake: bigint, totalPool: bigint, winningPool: bigint): bigint | null {
+  if (winningPool === BigInt(0)) return null;
+  return toU64((stake * totalPool) / winningPool);
+}
+
+/** Gross payout where the last winning claim takes whatever is left of the pool. */
+export function settledGross(
+  stake: bigint,
+  totalPool: bigint,
+  winningPool: bigint,
+  claimedTotal: bigint,
+  isLast: boolean,
+): bigint | null {
+  if (isLast) {
+    if (winningPool === BigInt(0)) return null;
+    return toU64(totalPool - claimedTotal);
+  }
+  return grossPayout(stake, totalPool, winningPool);
+}
+
+/** Splits a gross payout into `[payout, fee]`, the fee charged on profit only. */
+export function netPayout(gross: bigint, stake: bigint, feeBps: number, hostFeeBps: number): [bigint, bigint] | null {
+  const profit = toU64(gross - stake);
+  if (profit === null) return null;
+  const fee = (profit * BigInt(feeBps + hostFeeBps)) / BPS_DENOMINATOR;
+  return [gross - fee, fee];
+}
+
+/** Referrer's cut of a ticket's fee; it comes out of the fee, not the bettor's payout. */
+export function referralShare(fee: bigint, referralBps: number): bigint | null {
+  if (BigInt(referralBps) > BPS_DENOMINATOR) return null;
+  return (fee * BigInt(referralBps)) / BPS_DENOMINATOR;
+}
+
 /**
- * Computes the net payout in lamports for a winning ticket after platform and host fees.
+ * Lamports `claim_winnings` would pay `ticket` right now: the full stake when the market refunds
+ * (void, or no winners under the refund policy), otherwise the pro-rata share of the pool plus any
+ * rolled-in bonus pool, the last winning claim taking the remainder, minus fees on profit.
  */
 export function computeNetPayoutLamports(market: ParsedBetMarket, ticket: ParsedBetTicket): number {
+  const winningPool = market.winningSide === 0 ? market.poolYes : market.winningSide === 1 ? market.poolNo : 0;
+  const refund = market.winningSide === SIDE_VOID || (market.noWinnerPolicy === NO_WINNER_REFUND && winningPool === 0 && market.winningSide <= 1);
+  if (refund) return ticket.amount;
   if (market.winningSide !== ticket.side) return 0;
-  const winningPool = market.winningSide === 0 ? market.poolYes : market.poolNo;
-  if (!Number.isFinite(winningPool) || winningPool <= 0) return 0;
-  const totalPool = market.poolYes + market.poolNo;
-  if (!Number.isFinite(totalPool) || totalPool <= 0) return 0;
 
   try {
-    const ticketAmount = BigInt(Math.trunc(ticket.amount));
-    const poolTotal = BigInt(Math.trunc(totalPool));
-    const poolWinning = BigInt(Math.trunc(winningPool));
-    if (poolWinning === BigInt(0)) return 0;
-
-    const gross = (ticketAmount * poolTotal) / poolWinning;
-    const profit = gross - ticketAmount;
-    const totalFeeBps = BigInt(market.feeBps + market.hostFeeBps);
-    const totalFee = totalFeeBps > BigInt(0) ? (profit * totalFeeBps) / BigInt(10_000) : BigInt(0);
-    const payout = gross - totalFee;
-    return payout > BigInt(0) ? Number(payout) : 0;
+    const stake = BigInt(Math.trunc(ticket.amount));
+    const totalPool = BigInt(Math.trunc(market.poolYes)) + BigInt(Math.trunc(market.poolNo)) + BigInt(Math.trunc(market.bonusPool));
+    const winners = market.winningSide === 0 ? market.bettorsYes : market.bettorsNo;
+    const isLast = stake > BigInt(0) && market.claimedCount + 1 === winners;
+    const gross = settledGross(stake, totalPool, BigInt(Math.trunc(winningPool)), BigInt(Math.trunc(market.claimedTotal)), isLast);
+    if (gross === null) return 0;
+    const net = netPayout(gross, stake, market.feeBps, market.hostFeeBps);
+    return net === null ? 0 : Number(net[0]);
   } catch {
     return 0;
   }
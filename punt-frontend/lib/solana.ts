@@ -578,8 +578,8 @@ export function getAuthorityMetaPda(authority: PublicKey) {
 }
 
 export function getMarketPda(authority: PublicKey, cycle: number) {
-  const buf = Buffer.alloc(2);
-  buf.writeUInt16LE(cycle, 0);
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(cycle), 0);
   return PublicKey.findProgramAddressSync([
     Buffer.from("market"),
     authority.toBuffer(),
@@ -595,40 +595,35 @@ export function getTicketPda(market: PublicKey, user: PublicKey) {
   ], PROGRAM_ID)[0];
 }
 
-// AuthorityMeta fetch helper
-export interface RawAuthorityMeta { authority: PublicKey; nextCycle?: number; next_cycle?: number; bump: number; }
-export interface ParsedAuthorityMeta { authority: string; nextCycle: number; bump: number; }
+export function getTreasuryPda() {
+  return PublicKey.findProgramAddressSync([Buffer.from("treasury")], PROGRAM_ID)[0];
+}
 
-async function fetchAuthorityMeta(program: Program, authority: PublicKey): Promise<ParsedAuthorityMeta | null> {
-  const pda = getAuthorityMetaPda(authority);
-  try {
-  type RawAuthMetaPossible = { authority: PublicKey; nextCycle?: number; next_cycle?: number; bump: number };
-  const ns = program.account as unknown as { authorityMeta: { fetch: (pk: PublicKey) => Promise<RawAuthMetaPossible> } };
-  const raw = await ns.authorityMeta.fetch(pda);
-  const nextCycleVal = typeof raw.nextCycle === 'number' ? raw.nextCycle : (typeof raw.next_cycle === 'number' ? raw.next_cycle : 0);
-  return { authority: raw.authority.toBase58(), nextCycle: nextCycleVal, bump: raw.bump };
-  } catch (e) {
-    const msg = (e instanceof Error ? e.message : String(e)).toLowerCase();
-    if (msg.includes('account does not exist') || msg.includes('could not find')) return null;
-    return null;
-  }
+export function getReferrerVaultPda(referrer: PublicKey) {
+  return PublicKey.findProgramAddressSync([
+    Buffer.from("referrer_vault"),
+    referrer.toBuffer(),
+  ], PROGRAM_ID)[0];
 }
 
-// Initialize market (authority = connected wallet) - cycle aware (auto inits AuthorityMeta if needed)
+// AuthorityMeta account shape
+export interface RawAuthorityMeta { authority: PublicKey; nextCycle?: BN; next_cycle?: BN; bump: number; }
+
+// Initialize market (authority = connected wallet); auto inits AuthorityMeta if needed. Any unused
+// id is accepted, so the id is taken from the clock instead of reading `next_cycle` first.
 export async function initializeMarket(wallet: WalletLike, params: { title: string; labelYes: string; labelNo: string; feeBps?: number; }) {
   const { title, labelYes, labelNo, feeBps } = params;
   const program = await getProgram(wallet);
   const authority = wallet.publicKey!;
   const authorityMeta = getAuthorityMetaPda(authority);
-  let meta = await fetchAuthorityMeta(program, authority);
   const tx = new Transaction();
-  if (!meta) {
+  if ((await program.provider.connection.getAccountInfo(authorityMeta)) === null) {
     const ixInitMeta = await program.methods.initAuthorityMeta().accounts({ authority, authorityMeta, systemProgram: SystemProgram.programId }).instruction();
     tx.add(ixInitMeta);
-    meta = { authority: authority.toBase58(), nextCycle: 0, bump: 0 };
   }
-  const market = getMarketPda(authority, meta.nextCycle);
-  const ixInitMarket = await program.methods.initializeMarket(title, labelYes, labelNo, feeBps === undefined ? null : feeBps).accounts({
+  const cycle = Date.now();
+  const market = getMarketPda(authority, cycle);
+  const ixInitMarket = await program.methods.initializeMarket(new BN(cycle), title, labelYes, labelNo, feeBps === undefined ? null : feeBps, null).accounts({
     authority,
     authorityMeta,
     market,
@@ -636,32 +631,26 @@ export async function initializeMarket(wallet: WalletLike, params: { title: stri
   }).instruction();
   tx.add(ixInitMarket);
   const sig = await sendAndConfirmSafe(wallet, tx, 'initializeMarket');
-  return { txSig: sig, market, cycle: meta.nextCycle };
+  return { txSig: sig, market, cycle };
 }
 
-// Create a ticket for a given market authority (defaults to self for backwards compat)
-export async function createTicket(wallet: WalletLike, side: 0 | 1, marketAuthority?: PublicKey) {
+// Create a ticket on market `cycle` of a given market authority (defaults to self for backwards compat)
+export async function createTicket(wallet: WalletLike, side: 0 | 1, cycle: number, marketAuthority?: PublicKey) {
   const program = await getProgram(wallet);
   const user = wallet.publicKey!;
   const authority = marketAuthority ?? user;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market (meta missing or first cycle not initialized)');
-  const currentCycle = meta.nextCycle - 1;
-  const market = getMarketPda(authority, currentCycle);
+  const market = getMarketPda(authority, cycle);
   const ticket = getTicketPda(market, user);
-  const ix = await program.methods.createTicket(side).accounts({ user, market, ticket, systemProgram: SystemProgram.programId }).instruction();
+  const ix = await program.methods.createTicket(side, null).accounts({ user, market, ticket, referrerVault: null, systemProgram: SystemProgram.programId }).instruction();
   const tx = new Transaction().add(ix);
   const txSig = await sendAndConfirmSafe(wallet, tx, 'createTicket');
-  return { txSig, ticket, market, cycle: currentCycle };
+  return { txSig, ticket, market, cycle };
 }
 
-export async function placeBet(wallet: WalletLike, amountLamports: number, marketAuthority?: PublicKey) {
+export async function placeBet(wallet: WalletLike, amountLamports: number, cycle: number, marketAuthority?: PublicKey) {
   const program = await getProgram(wallet);
   const user = wallet.publicKey!;
   const authority = marketAuthority ?? user;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
   const ticket = getTicketPda(market, user);
   const ix = await program.methods.placeBet(new BN(amountLamports)).accounts({ user, market, ticket, systemProgram: SystemProgram.programId }).instruction();
@@ -671,14 +660,11 @@ export async function placeBet(wallet: WalletLike, amountLamports: number, marke
 }
 
 // Client-side unified bet: ensure ticket exists then place bet
-export async function bet(wallet: WalletLike, params: { side: 0 | 1; amountLamports: number; marketAuthority?: PublicKey }) {
-  const { side, amountLamports, marketAuthority } = params;
+export async function bet(wallet: WalletLike, params: { side: 0 | 1; amountLamports: number; cycle: number; marketAuthority?: PublicKey }) {
+  const { side, amountLamports, cycle, marketAuthority } = params;
   const program = await getProgram(wallet);
   const user = wallet.publicKey!;
   const authority = marketAuthority ?? user;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
   const ticket = getTicketPda(market, user);
   let needCreate = false;
@@ -688,7 +674,7 @@ export async function bet(wallet: WalletLike, params: { side: 0 | 1; amountLampo
   } catch { needCreate = true; }
   const tx = new Transaction();
   if (needCreate) {
-    const ixCreate = await program.methods.createTicket(side).accounts({ user, market, ticket, systemProgram: SystemProgram.programId }).instruction();
+    const ixCreate = await program.methods.createTicket(side, null).accounts({ user, market, ticket, referrerVault: null, systemProgram: SystemProgram.programId }).instruction();
     tx.add(ixCreate);
   } else {
     const ns = program.account as unknown as AccountNamespace;
@@ -701,12 +687,9 @@ export async function bet(wallet: WalletLike, params: { side: 0 | 1; amountLampo
   return { txSig: sig, ticket, market, cycle };
 }
 
-export async function freezeMarket(wallet: WalletLike) {
+export async function freezeMarket(wallet: WalletLike, cycle: number) {
   const program = await getProgram(wallet);
   const authority = wallet.publicKey!;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
   const ix = await program.methods.freezeMarket().accounts({ authority, market }).instruction();
   const tx = new Transaction().add(ix);
@@ -714,71 +697,63 @@ export async function freezeMarket(wallet: WalletLike) {
   return { txSig, cycle };
 }
 
-export async function resolveMarket(wallet: WalletLike, winningSide: 0 | 1) {
+export async function resolveMarket(wallet: WalletLike, cycle: number, winningSide: 0 | 1) {
   const program = await getProgram(wallet);
   const authority = wallet.publicKey!;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
-  const ix = await program.methods.resolveMarket(winningSide).accounts({ resolver: authority, market }).instruction();
+  const ix = await program.methods.resolveMarket(winningSide, Array(32).fill(0), 10_000, { manual: {} }).accounts({ resolver: authority, market }).instruction();
   const tx = new Transaction().add(ix);
   const txSig = await sendAndConfirmSafe(wallet, tx, 'resolveMarket');
   return { txSig, cycle };
 }
 
-export async function claimWinnings(wallet: WalletLike, marketAuthority?: PublicKey) {
+export async function claimWinnings(wallet: WalletLike, cycle: number, marketAuthority?: PublicKey) {
   const program = await getProgram(wallet);
   const user = wallet.publicKey!;
   const authority = marketAuthority ?? user;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
   const ticket = getTicketPda(market, user);
-  const ix = await program.methods.claimWinnings().accounts({ user, market, ticket }).instruction();
+  const ns = program.account as unknown as AccountNamespace;
+  const { referrer } = await ns.betTicket.fetch(ticket);
+  const referrerVault = referrer.equals(PublicKey.default) ? null : getReferrerVaultPda(referrer);
+  const ix = await program.methods.claimWinnings().accounts({ user, market, ticket, referrerVault }).instruction();
   const tx = new Transaction().add(ix);
   const txSig = await sendAndConfirmSafe(wallet, tx, 'claimWinnings');
   return { txSig, cycle };
 }
 
-export async function withdrawFees(wallet: WalletLike) {
+export async function withdrawFees(wallet: WalletLike, cycle: number) {
   const program = await getProgram(wallet);
   const authority = wallet.publicKey!;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
-  const ix = await program.methods.withdrawFees().accounts({ authority, market, host: HOST_PUBKEY }).instruction();
+  const ix = await program.methods.withdrawFees().accounts({ caller: authority, authority, market, treasury: getTreasuryPda(), feeSchedule: null }).instruction();
   const tx = new Transaction().add(ix);
   const txSig = await sendAndConfirmSafe(wallet, tx, 'withdrawFees');
   return { txSig, cycle };
 }
 
-export async function closeMarket(wallet: WalletLike) {
+export async function closeMarket(wallet: WalletLike, cycle: number) {
   const program = await getProgram(wallet);
   const authority = wallet.publicKey!;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
-  const ix = await program.methods.closeMarket().accounts({ authority, market, host: HOST_PUBKEY }).instruction();
+  const ix = await program.methods.closeMarket().accounts({ caller: authority, authority, market, treasury: getTreasuryPda(), feeSchedule: null }).instruction();
   const tx = new Transaction().add(ix);
   const txSig = await sendAndConfirmSafe(wallet, tx, 'closeMarket');
   return { txSig, cycle };
 }
 
 // Manually close a losing (or already claimed) ticket to reclaim rent if not auto-closed
-export async function closeTicket(wallet: WalletLike, marketAuthority?: PublicKey) {
+export async function closeTicket(wallet: WalletLike, cycle: number, marketAuthority?: PublicKey) {
   const program = await getProgram(wallet);
   const user = wallet.publicKey!;
   const authority = marketAuthority ?? user;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) throw new Error('No active market');
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
   const ticket = getTicketPda(market, user);
-  const ix = await program.methods.closeTicket().accounts({ user, market, ticket }).instruction();
+  // Once the market itself is closed only close_orphan_ticket can release the ticket rent.
+  const marketGone = (await _connection.getAccountInfo(market)) === null;
+  const ix = marketGone
+    ? await program.methods.closeOrphanTicket().accounts({ user, market, ticket }).instruction()
+    : await program.methods.closeTicket().accounts({ user, market, ticket }).instruction();
   const tx = new Transaction().add(ix);
   const txSig = await sendAndConfirmSafe(wallet, tx, 'closeTicket');
   return { txSig, cycle };
@@ -800,6 +775,14 @@ export interface ParsedBetMarket {
   title: string;
   labelYes: string;
   labelNo: string;
+  // Settlement state, as read by claim_winnings (see lib/payout.ts)
+  bettorsYes: number;
+  bettorsNo: number;
+  claimedTotal: number;
+  claimedCount: number;
+  noWinnerPolicy: number; // 0 fees, 1 refund, 2 rollover
+  bonusPool: number;
+  referralBps: number;
 }
 export interface RawBetTicket {
   user: PublicKey;
@@ -808,6 +791,7 @@ export interface RawBetTicket {
   amount: BN;
   claimed: boolean;
   bump: number;
+  referrer: PublicKey;
 }
 export interface ParsedBetTicket {
   user: string;
@@ -841,26 +825,31 @@ interface TicketAccountNamespace {
 
 const TITLE_MAX_LEN_BYTES = 64;
 const LABEL_MAX_LEN_BYTES = 32;
-const BET_MARKET_ACCOUNT_LEN_V1 = 8 + 32 + 2 + 8 + 8 + 1 + 2 + 2 + 1 + 1 + 8 + TITLE_MAX_LEN_BYTES + LABEL_MAX_LEN_BYTES + LABEL_MAX_LEN_BYTES;
-const BET_MARKET_ACCOUNT_LEN_V2 = BET_MARKET_ACCOUNT_LEN_V1 + 1;
+// Prefix of the BetMarket layout since market ids widened to u64 (authority, u64 cycle, pools,
+// flags, fees, text). Markets with the older u16 id live at different PDAs and are never read here.
+const BET_MARKET_ACCOUNT_LEN_U64_ID = 8 + 32 + 8 + 8 + 8 + 1 + 1 + 2 + 2 + 1 + 1 + 8 + TITLE_MAX_LEN_BYTES + LABEL_MAX_LEN_BYTES + LABEL_MAX_LEN_BYTES;
+// Fields between claimed_count and no_winner_policy that the frontend does not read (limits,
+// dispute, quorum, evidence, oracle, schedule and prize-draw settings).
+const BET_MARKET_SKIPPED_SETTINGS_LEN = 8 + 8 + 8 + 1 + 8 + 4 + 8 + 1 + 2 + 8 + 32 + 2 + 1 + 1 + 32 + 8 + 8 + 8 + 8 + 32 + 1 + 32;
+// Everything parsed below, through referral_bps.
+const BET_MARKET_PARSED_LEN = BET_MARKET_ACCOUNT_LEN_U64_ID + 4 + 4 + 8 + 4 + BET_MARKET_SKIPPED_SETTINGS_LEN + 1 + 8 + 8 + 2;
+
+const BET_MARKET_DISCRIMINATOR = (PUNT_PROGRAM_IDL as unknown as { accounts?: Array<{ name: string; discriminator?: number[] }> })
+  .accounts?.find(a => a.name === 'BetMarket')?.discriminator;
 
 function parseBetMarketAccount(data: Uint8Array): ParsedBetMarket {
   const len = data.length;
-  if (len !== BET_MARKET_ACCOUNT_LEN_V1 && len !== BET_MARKET_ACCOUNT_LEN_V2) {
+  if (len < BET_MARKET_PARSED_LEN) {
     throw new Error(`Unsupported BetMarket account length ${len}`);
   }
   const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
   let offset = 8; // skip discriminator
   const authority = new PublicKey(data.slice(offset, offset + 32)); offset += 32;
-  const cycle = view.getUint16(offset, true); offset += 2;
+  const cycle = Number(view.getBigUint64(offset, true)); offset += 8;
   const poolYes = Number(view.getBigUint64(offset, true)); offset += 8;
   const poolNo = Number(view.getBigUint64(offset, true)); offset += 8;
   const resolved = data[offset] === 1; offset += 1;
-  let frozen = false;
-  if (len === BET_MARKET_ACCOUNT_LEN_V2) {
-    frozen = data[offset] === 1;
-    offset += 1;
-  }
+  const frozen = data[offset] === 1; offset += 1;
   const feeBps = view.getUint16(offset, true); offset += 2;
   const hostFeeBps = view.getUint16(offset, true); offset += 2;
   const bump = data[offset]; offset += 1;
@@ -868,7 +857,16 @@ function parseBetMarketAccount(data: Uint8Array): ParsedBetMarket {
   const feesAccrued = Number(view.getBigUint64(offset, true)); offset += 8;
   const titleBytes = data.slice(offset, offset + TITLE_MAX_LEN_BYTES); offset += TITLE_MAX_LEN_BYTES;
   const labelYesBytes = data.slice(offset, offset + LABEL_MAX_LEN_BYTES); offset += LABEL_MAX_LEN_BYTES;
-  const labelNoBytes = data.slice(offset, offset + LABEL_MAX_LEN_BYTES);
+  const labelNoBytes = data.slice(offset, offset + LABEL_MAX_LEN_BYTES); offset += LABEL_MAX_LEN_BYTES;
+  const bettorsYes = view.getUint32(offset, true); offset += 4;
+  const bettorsNo = view.getUint32(offset, true); offset += 4;
+  const claimedTotal = Number(view.getBigUint64(offset, true)); offset += 8;
+  const claimedCount = view.getUint32(offset, true); offset += 4;
+  offset += BET_MARKET_SKIPPED_SETTINGS_LEN;
+  const noWinnerPolicy = data[offset]; offset += 1;
+  const bonusPool = Number(view.getBigUint64(offset, true)); offset += 8;
+  offset += 8; // rollover_pending
+  const referralBps = view.getUint16(offset, true);
   return {
     authority: authority.toBase58(),
     cycle,
@@ -884,15 +882,42 @@ function parseBetMarketAccount(data: Uint8Array): ParsedBetMarket {
     title: bytesToStr(titleBytes),
     labelYes: bytesToStr(labelYesBytes),
     labelNo: bytesToStr(labelNoBytes),
+    bettorsYes,
+    bettorsNo,
+    claimedTotal,
+    claimedCount,
+    noWinnerPolicy,
+    bonusPool,
+    referralBps,
   };
 }
 
-export async function fetchMarket(wallet: WalletLike, marketAuthority?: PublicKey): Promise<{ pubkey: PublicKey; data: ParsedBetMarket } | null> {
+// Every open market of the authority, oldest id first. Ids are client-chosen, so markets are found
+// by scanning for BetMarket accounts with this authority rather than derived from AuthorityMeta.
+export async function fetchAuthorityMarkets(marketAuthority: PublicKey): Promise<Array<{ pubkey: PublicKey; data: ParsedBetMarket }>> {
+  const program = await getReadonlyProgram();
+  if (!BET_MARKET_DISCRIMINATOR) throw new Error('BetMarket discriminator missing from IDL');
+  const accounts = await program.provider.connection.getProgramAccounts(PROGRAM_ID, {
+    commitment: 'processed',
+    filters: [
+      { memcmp: { offset: 0, bytes: bs58.encode(Uint8Array.from(BET_MARKET_DISCRIMINATOR)) } },
+      { memcmp: { offset: 8, bytes: marketAuthority.toBase58() } },
+    ],
+  });
+  const markets: Array<{ pubkey: PublicKey; data: ParsedBetMarket }> = [];
+  for (const { pubkey, account } of accounts) {
+    try {
+      markets.push({ pubkey, data: parseBetMarketAccount(account.data) });
+    } catch (e) {
+      console.warn('[fetchAuthorityMarkets] Failed to decode market account', pubkey.toBase58(), e);
+    }
+  }
+  return markets.sort((a, b) => a.data.cycle - b.data.cycle);
+}
+
+export async function fetchMarket(wallet: WalletLike, cycle: number, marketAuthority?: PublicKey): Promise<{ pubkey: PublicKey; data: ParsedBetMarket } | null> {
   const program = await getProgram(wallet);
   const authority = marketAuthority ?? wallet.publicKey!;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) return null; // no markets yet
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
   try {
     const info = await program.provider.connection.getAccountInfo(market, { commitment: 'processed' });
@@ -906,12 +931,8 @@ export async function fetchMarket(wallet: WalletLike, marketAuthority?: PublicKe
 }
 
 // Public (read-only) fetch that does not require a connected wallet (for viewers)
-export async function fetchMarketPublic(marketAuthority: PublicKey): Promise<{ pubkey: PublicKey; data: ParsedBetMarket } | null> {
+export async function fetchMarketPublic(marketAuthority: PublicKey, cycle: number): Promise<{ pubkey: PublicKey; data: ParsedBetMarket } | null> {
   const program = await getReadonlyProgram();
-  // Use authority meta to determine current cycle
-  const meta = await fetchAuthorityMeta(program, marketAuthority);
-  if (!meta || meta.nextCycle === 0) return null;
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(marketAuthority, cycle);
   try {
     const info = await program.provider.connection.getAccountInfo(market, { commitment: 'processed' });
@@ -924,11 +945,8 @@ export async function fetchMarketPublic(marketAuthority: PublicKey): Promise<{ p
   }
 }
 
-export async function fetchMarketTicketCountPublic(marketAuthority: PublicKey): Promise<number> {
+export async function fetchMarketTicketCountPublic(marketAuthority: PublicKey, cycle: number): Promise<number> {
   const program = await getReadonlyProgram();
-  const meta = await fetchAuthorityMeta(program, marketAuthority);
-  if (!meta || meta.nextCycle === 0) return 0;
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(marketAuthority, cycle);
   try {
     const ticketNs = program.account as unknown as TicketAccountNamespace;
@@ -950,13 +968,10 @@ export async function fetchMarketTicketCountPublic(marketAuthority: PublicKey):
   }
 }
 
-export async function fetchTicket(wallet: WalletLike, marketAuthority?: PublicKey): Promise<{ pubkey: PublicKey; data: ParsedBetTicket } | null> {
+export async function fetchTicket(wallet: WalletLike, cycle: number, marketAuthority?: PublicKey): Promise<{ pubkey: PublicKey; data: ParsedBetTicket } | null> {
   const program = await getProgram(wallet);
   const user = wallet.publicKey!;
   const authority = marketAuthority ?? user;
-  const meta = await fetchAuthorityMeta(program, authority);
-  if (!meta || meta.nextCycle === 0) return null;
-  const cycle = meta.nextCycle - 1;
   const market = getMarketPda(authority, cycle);
   const ticket = getTicketPda(market, user);
   try {
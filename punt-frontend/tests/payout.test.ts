@@ -1,7 +1,8 @@
 import test from "node:test";
 import assert from "node:assert/strict";
-import { computeNetPayoutLamports } from "../lib/payout";
+import { computeNetPayoutLamports, netPayout, referralShare, settledGross } from "../lib/payout";
 import type { ParsedBetMarket, ParsedBetTicket } from "../lib/solana";
+import vectors from "../../punt-program/crates/punt-math/tests/vectors.json";
 
 const baseMarket: ParsedBetMarket = {
   authority: "auth",
@@ -18,6 +19,13 @@ const baseMarket: ParsedBetMarket = {
   title: "Test Market",
   labelYes: "YES",
   labelNo: "NO",
+  bettorsYes: 2,
+  bettorsNo: 3,
+  claimedTotal: 0,
+  claimedCount: 0,
+  noWinnerPolicy: 0,
+  bonusPool: 0,
+  referralBps: 0,
 };
 
 const baseTicket: ParsedBetTicket = {
@@ -50,3 +58,44 @@ test("guards against zero winning pool", () => {
   );
   assert.equal(payout, 0);
 });
+
+test("last winning claim takes the rest of the pool", () => {
+  // Three 1-lamport winners share a 10-lamport pool: 3 + 3 + 4 (fees round to zero).
+  const market = { ...baseMarket, poolYes: 3, poolNo: 7, bettorsYes: 3 };
+  const ticket = { ...baseTicket, amount: 1 };
+  assert.equal(computeNetPayoutLamports({ ...market, claimedCount: 1, claimedTotal: 3 }, ticket), 3);
+  assert.equal(computeNetPayoutLamports({ ...market, claimedCount: 2, claimedTotal: 6 }, ticket), 4);
+});
+
+test("rolled-in bonus pool is shared by the winners", () => {
+  const payout = computeNetPayoutLamports({ ...baseMarket, bonusPool: 500 }, baseTicket);
+  // Gross 1_000 * 10_500 / 4_000 = 2_625. Fees 300 bps of 1_625 => 48. Net => 2_577.
+  assert.equal(payout, 2_577);
+});
+
+test("void and refund-policy markets return the stake", () => {
+  assert.equal(computeNetPayoutLamports({ ...baseMarket, winningSide: 2 }, { ...baseTicket, side: 1 }), 1_000);
+  const noWinners = { ...baseMarket, poolYes: 0, bettorsYes: 0, noWinnerPolicy: 1 };
+  assert.equal(computeNetPayoutLamports(noWinners, { ...baseTicket, side: 1 }), 1_000);
+});
+
+test("matches the punt-math settled_gross vectors", () => {
+  for (const v of vectors.settled_gross) {
+    const got = settledGross(BigInt(v.stake), BigInt(v.total_pool), BigInt(v.winning_pool), BigInt(v.claimed_total), v.is_last);
+    assert.equal(got === null ? null : got.toString(), v.expected, v.note);
+  }
+});
+
+test("matches the punt-math net_payout vectors", () => {
+  for (const v of vectors.net_payout) {
+    const got = netPayout(BigInt(v.gross), BigInt(v.stake), v.fee_bps, v.host_fee_bps);
+    assert.deepEqual(got === null ? null : got.map(String), v.expected, v.note);
+  }
+});
+
+test("matches the punt-math referral_share vectors", () => {
+  for (const v of vectors.referral_share) {
+    const got = referralShare(BigInt(v.fee), v.referral_bps);
+    assert.equal(got === null ? null : got.toString(), v.expected);
+  }
+});
@@ -0,0 +1,93 @@
+//! Human-readable formatting of market state: SOL amounts, pool odds and lifecycle status.
+
+use anyhow::{bail, Result};
+use punt_program::{BetMarket, DISPUTE_CHALLENGED, SIDE_VOID};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+pub fn sol(lamports: u64) -> String {
+    format!("{}.{:09}", lamports / LAMPORTS_PER_SOL, lamports % LAMPORTS_PER_SOL)
+}
+
+/// Parses a decimal SOL amount ("1", "0.25") into lamports without going through floats.
+pub fn parse_sol(s: &str) -> Result<u64> {
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    if (whole.is_empty() && frac.is_empty()) || frac.len() > 9 {
+        bail!("invalid SOL amount {s:?} (at most 9 decimals)");
+    }
+    if !whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+        bail!("invalid SOL amount {s:?}");
+    }
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse()? };
+    let frac: u64 = format!("{frac:0<9}").parse()?;
+    whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|l| l.checked_add(frac))
+        .ok_or_else(|| anyhow::anyhow!("SOL amount {s:?} out of range"))
+}
+
+/// Share of the pool on one side and the gross payout multiple a winning stake would get
+/// at the current pools, or `None` while that side is empty.
+pub fn odds(side_pool: u64, other_pool: u64) -> Option<(f64, f64)> {
+    if side_pool == 0 {
+        return None;
+    }
+    let total = side_pool as f64 + other_pool as f64;
+    Some((side_pool as f64 / total * 100.0, total / side_pool as f64))
+}
+
+pub fn odds_line(label: &str, side_pool: u64, other_pool: u64, bettors: u32) -> String {
+    let odds = match odds(side_pool, other_pool) {
+        Some((pct, mult)) => format!("{pct:5.1}%  pays {mult:.2}x"),
+        None => "    -   no bets".to_string(),
+    };
+    format!("{label:<32} {} SOL  {bettors:>4} bettors  {odds}", sol(side_pool))
+}
+
+pub fn status(m: &BetMarket, label_yes: &str, label_no: &str) -> String {
+    if !m.resolved {
+        return if m.frozen { "frozen".into() } else { "open".into() };
+    }
+    let winner = match m.winning_side {
+        0 => label_yes,
+        1 => label_no,
+        SIDE_VOID => "void",
+        _ => "?",
+    };
+    if m.dispute_state == DISPUTE_CHALLENGED {
+        format!("resolved: {winner} (disputed)")
+    } else {
+        format!("resolved: {winner}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sol_amounts_exactly() {
+        assert_eq!(parse_sol("1").unwrap(), 1_000_000_000);
+        assert_eq!(parse_sol("0.25").unwrap(), 250_000_000);
+        assert_eq!(parse_sol(".000000001").unwrap(), 1);
+        assert!(parse_sol("0.0000000001").is_err());
+        assert!(parse_sol("-1").is_err());
+        assert!(parse_sol("1e9").is_err());
+        assert!(parse_sol(".").is_err());
+        assert!(parse_sol("99999999999").is_err());
+    }
+
+    #[test]
+    fn formats_lamports_as_sol() {
+        assert_eq!(sol(1_500_000_000), "1.500000000");
+        assert_eq!(sol(1), "0.000000001");
+    }
+
+    #[test]
+    fn odds_follow_pool_shares() {
+        let (pct, mult) = odds(3, 1).unwrap();
+        assert!((pct - 75.0).abs() < 1e-9);
+        assert!((mult - 4.0 / 3.0).abs() < 1e-9);
+        assert_eq!(odds(0, 5), None);
+    }
+}
@@ -0,0 +1,671 @@
+//! punt: operator CLI for punt-program. Every command signs with the keypair from `punt.toml`
+//! (market authority for `market`, `template`, `fees` and `meta`; bettor for `ticket`; referrer
+//! for `referral`; treasury admin for `treasury withdraw` and `set-admin`).
+
+mod config;
+mod display;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use config::Config;
+use punt_client::{accounts, ix, keypair, pda, RpcClient};
+use punt_program::{
+    BetLimits, BetMarket, DisputeConfig, FeeSchedule, MarketKind, MarketOptions, NoWinnerPolicy, PrizeWeighting,
+    ResolutionSource, UnclaimedPolicy, AUTHORITY_FEE_BPS_DEFAULT, SIDE_VOID,
+};
+use sha2::{Digest, Sha256};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(name = "punt", about = "Operate punt-program markets from the command line")]
+struct Cli {
+    /// Path to punt.toml
+    #[arg(long, global = true, default_value = "punt.toml")]
+    config: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(subcommand)]
+    Market(MarketCmd),
+    #[command(subcommand)]
+    Ticket(TicketCmd),
+    #[command(subcommand)]
+    Template(TemplateCmd),
+    #[command(subcommand)]
+    Prize(PrizeCmd),
+    #[command(subcommand)]
+    Fees(FeesCmd),
+    #[command(subcommand)]
+    Referral(ReferralCmd),
+    #[command(subcommand)]
+    Treasury(TreasuryCmd),
+    #[command(subcommand)]
+    Meta(MetaCmd),
+}
+
+#[derive(Subcommand)]
+enum MarketCmd {
+    /// Create a market.
+    Create(CreateArgs),
+    /// Create a market from a saved template.
+    FromTemplate {
+        id: u16,
+        /// Market id (defaults to the authority's next_cycle).
+        #[arg(long)]
+        market_id: Option<u64>,
+    },
+    /// Stop betting ahead of resolution.
+    Freeze { market: Pubkey },
+    /// Resolve a frozen market.
+    Resolve {
+        market: Pubkey,
+        /// Winning side: yes/no (or 0/1), or void for a no contest that refunds every ticket.
+        #[arg(long, value_parser = parse_outcome)]
+        side: u8,
+        /// File whose SHA-256 is recorded as the evidence hash.
+        #[arg(long)]
+        evidence: Option<PathBuf>,
+        /// Resolver confidence in basis points.
+        #[arg(long, default_value_t = 10_000)]
+        confidence_bps: u16,
+    },
+    /// Refund a market still unresolved a week after betting closed (anyone may).
+    VoidStale { market: Pubkey },
+    /// Move payouts left unclaimed past the claim deadline out of escrow (anyone may).
+    Sweep { market: Pubkey },
+    /// Carry a no-winner market's pool into a later market of yours.
+    Rollover {
+        market: Pubkey,
+        /// Market id of the destination (must be later and unresolved).
+        #[arg(long)]
+        into: u64,
+    },
+    /// Send a pool never rolled over to the treasury once its rollover deadline has passed (anyone may).
+    ExpireRollover { market: Pubkey },
+    /// Close a settled market, returning its rent to the authority (anyone may once all payouts are claimed).
+    Close { market: Pubkey },
+    /// Print a market's text, pools, odds and settings.
+    Show { market: Pubkey },
+    /// List markets, optionally for one authority.
+    List {
+        #[arg(long)]
+        authority: Option<Pubkey>,
+    },
+}
+
+#[derive(Args)]
+struct CreateArgs {
+    /// Market id, any without an open market (defaults to the authority's next_cycle).
+    #[arg(long)]
+    market_id: Option<u64>,
+    #[arg(long)]
+    title: String,
+    #[arg(long)]
+    label_yes: String,
+    #[arg(long)]
+    label_no: String,
+    /// Authority fee in basis points (program default if omitted).
+    #[arg(long)]
+    fee_bps: Option<u16>,
+    /// Minimum bet in SOL.
+    #[arg(long, value_parser = display::parse_sol)]
+    min_bet: Option<u64>,
+    /// Maximum stake per ticket in SOL.
+    #[arg(long, value_parser = display::parse_sol)]
+    max_bet: Option<u64>,
+    /// Maximum combined pool in SOL.
+    #[arg(long, value_parser = display::parse_sol)]
+    max_pool: Option<u64>,
+    /// Dispute window after resolution, in seconds.
+    #[arg(long)]
+    dispute_window_secs: Option<u32>,
+    /// Challenge bond in SOL.
+    #[arg(long, value_parser = display::parse_sol)]
+    dispute_bond: Option<u64>,
+    /// Pools at or above this many SOL must resolve by quorum vote.
+    #[arg(long, value_parser = display::parse_sol)]
+    quorum_threshold: Option<u64>,
+    /// Unix time at which betting closes (keepers freeze after it).
+    #[arg(long)]
+    close_ts: Option<i64>,
+    /// Every ticket stakes exactly this many SOL, once (event markets).
+    #[arg(long, value_parser = display::parse_sol)]
+    fixed_stake: Option<u64>,
+    /// Share of a referred ticket's fee paid to its referrer, in basis points of the fee.
+    #[arg(long)]
+    referral_bps: Option<u16>,
+    /// What happens to the pool if the winning side has no bets: fees, refund or rollover.
+    #[arg(long, value_parser = parse_no_winner_policy, default_value = "fees")]
+    no_winner: NoWinnerPolicy,
+    /// Claims expire this many seconds after the market becomes final, or after a prize draw's
+    /// reveal window (at least 604800; never if omitted).
+    #[arg(long)]
+    claim_window_secs: Option<u32>,
+    /// Who receives payouts still unclaimed at the deadline: treasury or authority.
+    #[arg(long, value_parser = parse_unclaimed_policy, default_value = "treasury")]
+    unclaimed_to: UnclaimedPolicy,
+}
+
+#[derive(Subcommand)]
+enum TicketCmd {
+    /// Open a ticket on one side of a market.
+    Create {
+        market: Pubkey,
+        #[arg(long, value_parser = parse_side)]
+        side: u8,
+        /// Referrer credited with a share of this ticket's fees (must have run `referral init`).
+        #[arg(long)]
+        referrer: Option<Pubkey>,
+    },
+    /// Add a stake (in SOL) to your ticket.
+    Bet {
+        market: Pubkey,
+        #[arg(value_parser = display::parse_sol)]
+        amount: u64,
+    },
+    /// Claim your payout once the market is final.
+    Claim { market: Pubkey },
+    /// Close your settled ticket to recover its rent (also after the market itself was closed).
+    Close { market: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum TemplateCmd {
+    /// Save a market preset under the authority.
+    Create(TemplateArgs),
+    /// Delete a template and recover its rent.
+    Close { id: u16 },
+    /// List the authority's templates.
+    List,
+}
+
+#[derive(Args)]
+struct TemplateArgs {
+    id: u16,
+    #[arg(long)]
+    title: String,
+    #[arg(long)]
+    label_yes: String,
+    #[arg(long)]
+    label_no: String,
+    /// Authority fee in basis points.
+    #[arg(long, default_value_t = AUTHORITY_FEE_BPS_DEFAULT)]
+    fee_bps: u16,
+    /// Minimum bet in SOL.
+    #[arg(long, value_parser = display::parse_sol)]
+    min_bet: Option<u64>,
+    /// Maximum stake per ticket in SOL.
+    #[arg(long, value_parser = display::parse_sol)]
+    max_bet: Option<u64>,
+    /// Maximum combined pool in SOL.
+    #[arg(long, value_parser = display::parse_sol)]
+    max_pool: Option<u64>,
+    /// Betting window in seconds from market creation (0 = freeze manually).
+    #[arg(long, default_value_t = 0)]
+    duration_secs: u32,
+}
+
+#[derive(Subcommand)]
+enum PrizeCmd {
+    /// Commit to a prize-draw seed before the market is frozen.
+    Commit {
+        market: Pubkey,
+        /// Secret file; its SHA-256 is the seed. Keep it until the draw.
+        #[arg(long)]
+        seed_file: PathBuf,
+        /// Weight entries by stake instead of one per winning ticket.
+        #[arg(long)]
+        by_stake: bool,
+    },
+    /// Reveal the seed and draw the winner among the market's winning tickets (anyone holding
+    /// the seed may).
+    Draw {
+        market: Pubkey,
+        #[arg(long)]
+        seed_file: PathBuf,
+        /// Winning tickets per transaction.
+        #[arg(long, default_value_t = 20)]
+        page: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum FeesCmd {
+    /// Pay accrued fees out to the treasury and the authority (or the market's fee schedule); anyone
+    /// may once all payouts are claimed.
+    Withdraw { market: Pubkey },
+    /// Split the authority's fee share across recipients; only before the first bet.
+    Schedule {
+        market: Pubkey,
+        /// Recipient and weight as PUBKEY:BPS (repeatable; weights must sum to 10000).
+        #[arg(long = "recipient", value_parser = parse_fee_share, required = true)]
+        recipients: Vec<(Pubkey, u16)>,
+        /// Index of the recipient that takes the rounding remainder.
+        #[arg(long, default_value_t = 0)]
+        remainder: u8,
+    },
+}
+
+#[derive(Subcommand)]
+enum TreasuryCmd {
+    /// One-time creation of the platform treasury (any payer).
+    Init,
+    /// Pay collected host fees out of the treasury (treasury admin key only).
+    Withdraw {
+        /// Amount in SOL.
+        #[arg(value_parser = display::parse_sol)]
+        amount: u64,
+        /// Recipient (defaults to the signing keypair).
+        #[arg(long)]
+        to: Option<Pubkey>,
+    },
+    /// Hand the treasury to a new admin key (current treasury admin only).
+    SetAdmin { admin: Pubkey },
+    /// Print the treasury balance and lifetime totals.
+    Show,
+}
+
+#[derive(Subcommand)]
+enum ReferralCmd {
+    /// Open your referral vault so tickets can name you as referrer.
+    Init,
+    /// Pay your accrued referral rewards out to your wallet.
+    Withdraw,
+    /// Print your accrued and lifetime referral rewards.
+    Show,
+}
+
+#[derive(Subcommand)]
+enum MetaCmd {
+    /// One-time setup of the authority's market id counter.
+    Init,
+    /// Upgrade an authority meta created before market ids became 64-bit (refused while any of
+    /// your markets still use the older layout).
+    Migrate,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let cfg = Config::load(&cli.config)?;
+    let rpc = RpcClient::new(&cfg.rpc_url);
+    let signer = keypair::read_keypair(&cfg.keypair)?;
+    let me = signer.pubkey();
+
+    match cli.command {
+        Command::Market(cmd) => match cmd {
+            MarketCmd::Create(args) => create_market(&rpc, &signer, args),
+            MarketCmd::FromTemplate { id, market_id } => {
+                let cycle = match market_id {
+                    Some(cycle) => cycle,
+                    None => next_cycle(&rpc, &me)?,
+                };
+                send(&rpc, &signer, ix::initialize_market_from_template(&me, id, cycle))?;
+                println!("market {} (cycle {cycle})", pda::market(&me, cycle));
+                Ok(())
+            }
+            MarketCmd::Freeze { market } => send(&rpc, &signer, ix::freeze_market(&me, &market)),
+            MarketCmd::Resolve { market, side, evidence, confidence_bps } => {
+                let evidence_hash = match evidence {
+                    Some(path) => {
+                        let bytes = std::fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+                        Sha256::digest(&bytes).into()
+                    }
+                    None => [0; 32],
+                };
+                let ix = ix::resolve_market(&me, &market, side, evidence_hash, confidence_bps, ResolutionSource::Manual);
+                send(&rpc, &signer, ix)
+            }
+            MarketCmd::VoidStale { market } => send(&rpc, &signer, ix::void_stale_market(&market)),
+            MarketCmd::Sweep { market } => {
+                let m = accounts::fetch_market(&rpc, &market)?.with_context(|| format!("market {market} not found"))?;
+                send(&rpc, &signer, ix::sweep_unclaimed(&me, &m.authority, &market))
+            }
+            MarketCmd::Rollover { market, into } => {
+                send(&rpc, &signer, ix::roll_over_pool(&me, &market, &pda::market(&me, into)))
+            }
+            MarketCmd::ExpireRollover { market } => send(&rpc, &signer, ix::expire_rollover(&market)),
+            MarketCmd::Close { market } => {
+                let m = accounts::fetch_market(&rpc, &market)?.with_context(|| format!("market {market} not found"))?;
+                let schedule = if m.has_fee_schedule { accounts::fetch_fee_schedule(&rpc, &market)? } else { None };
+                send(&rpc, &signer, ix::close_market(&me, &m.authority, &market, schedule.as_ref()))
+            }
+            MarketCmd::Show { market } => {
+                let m = accounts::fetch_market(&rpc, &market)?.with_context(|| format!("market {market} not found"))?;
+                let schedule = if m.has_fee_schedule { accounts::fetch_fee_schedule(&rpc, &market)? } else { None };
+                show(&market, &m, schedule.as_ref());
+                Ok(())
+            }
+            MarketCmd::List { authority } => {
+                let mut markets = accounts::fetch_markets(&rpc)?;
+                markets.retain(|(_, m)| authority.is_none_or(|a| m.authority == a));
+                markets.sort_by_key(|(_, m)| (m.authority, m.cycle));
+                for (key, m) in markets {
+                    let (yes, no) = (accounts::fixed_str(&m.label_yes), accounts::fixed_str(&m.label_no));
+                    println!(
+                        "{key}  cycle {:<4} {:<24} {} SOL  \"{}\"",
+                        m.cycle,
+                        display::status(&m, &yes, &no),
+                        display::sol(m.pool_yes.saturating_add(m.pool_no)),
+                        accounts::fixed_str(&m.title)
+                    );
+                }
+                Ok(())
+            }
+        },
+        Command::Ticket(cmd) => match cmd {
+            TicketCmd::Create { market, side, referrer } => {
+                send(&rpc, &signer, ix::create_ticket(&me, &market, side, referrer.as_ref()))
+            }
+            TicketCmd::Bet { market, amount } => send(&rpc, &signer, ix::place_bet(&me, &market, amount)),
+            TicketCmd::Claim { market } => {
+                let ticket = pda::ticket(&market, &me);
+                let t = accounts::fetch_ticket(&rpc, &ticket)?.with_context(|| format!("no ticket on {market}"))?;
+                send(&rpc, &signer, ix::claim_winnings(&me, &market, &t.referrer))
+            }
+            TicketCmd::Close { market } => {
+                if rpc.get_account_data(&market)?.is_some() {
+                    send(&rpc, &signer, ix::close_ticket(&me, &market))
+                } else {
+                    send(&rpc, &signer, ix::close_orphan_ticket(&me, &market))
+                }
+            }
+        },
+        Command::Template(cmd) => match cmd {
+            TemplateCmd::Create(args) => {
+                let limits = BetLimits {
+                    min_bet: args.min_bet.unwrap_or(0),
+                    max_bet_per_ticket: args.max_bet.unwrap_or(0),
+                    max_pool: args.max_pool.unwrap_or(0),
+                };
+                let ix = ix::create_market_template(
+                    &me,
+                    args.id,
+                    args.title,
+                    args.label_yes,
+                    args.label_no,
+                    args.fee_bps,
+                    limits,
+                    args.duration_secs,
+                );
+                send(&rpc, &signer, ix)?;
+                println!("template {}", pda::template(&me, args.id));
+                Ok(())
+            }
+            TemplateCmd::Close { id } => send(&rpc, &signer, ix::close_market_template(&me, id)),
+            TemplateCmd::List => {
+                let mut templates = accounts::fetch_templates(&rpc, &me)?;
+                templates.sort_by_key(|(_, t)| t.id);
+                for (_, t) in templates {
+                    println!(
+                        "{:<5} \"{}\" ({} / {})  fee {} bps  window {}s",
+                        t.id,
+                        accounts::fixed_str(&t.title),
+                        accounts::fixed_str(&t.label_yes),
+                        accounts::fixed_str(&t.label_no),
+                        t.fee_bps,
+                        t.duration_secs
+                    );
+                }
+                Ok(())
+            }
+        },
+        Command::Prize(cmd) => match cmd {
+            PrizeCmd::Commit { market, seed_file, by_stake } => {
+                let seed_hash = Sha256::digest(read_seed(&seed_file)?).into();
+                let weighting = if by_stake { PrizeWeighting::ByStake } else { PrizeWeighting::PerTicket };
+                send(&rpc, &signer, ix::commit_prize_seed(&me, &market, seed_hash, weighting))
+            }
+            PrizeCmd::Draw { market, seed_file, page } => {
+                let m = accounts::fetch_market(&rpc, &market)?.with_context(|| format!("market {market} not found"))?;
+                let winners: Vec<_> = accounts::fetch_tickets(&rpc, &market)?
+                    .into_iter()
+                    .filter(|(_, t)| t.side == m.winning_side && t.amount > 0)
+                    .collect();
+                let seed = read_seed(&seed_file)?;
+                for tickets in ix::prize_draw_pages(&winners, page) {
+                    send(&rpc, &signer, ix::draw_prize_winner(&market, seed, &tickets))?;
+                }
+                if let Some(m) = accounts::fetch_market(&rpc, &market)? {
+                    println!("prize winner {}", m.prize_winner);
+                }
+                Ok(())
+            }
+        },
+        Command::Fees(cmd) => match cmd {
+            FeesCmd::Withdraw { market } => {
+                let m = accounts::fetch_market(&rpc, &market)?.with_context(|| format!("market {market} not found"))?;
+                let schedule = accounts::fetch_fee_schedule(&rpc, &market)?;
+                send(&rpc, &signer, ix::withdraw_fees(&me, &m.authority, &market, schedule.as_ref()))
+            }
+            FeesCmd::Schedule { market, recipients, remainder } => {
+                let (recipients, weights) = recipients.into_iter().unzip();
+                send(&rpc, &signer, ix::set_fee_schedule(&me, &market, recipients, weights, remainder))
+            }
+        },
+        Command::Treasury(cmd) => match cmd {
+            TreasuryCmd::Init => send(&rpc, &signer, ix::init_treasury(&me)),
+            TreasuryCmd::Withdraw { amount, to } => {
+                send(&rpc, &signer, ix::treasury_withdraw(&me, &to.unwrap_or(me), amount))
+            }
+            TreasuryCmd::SetAdmin { admin } => send(&rpc, &signer, ix::set_treasury_admin(&me, &admin)),
+            TreasuryCmd::Show => {
+                let t = accounts::fetch_treasury(&rpc)?.context("treasury not created; run `punt treasury init`")?;
+                println!("treasury   {}", pda::treasury());
+                println!("admin      {}", t.admin);
+                println!("available  {} SOL", display::sol(t.collected.saturating_sub(t.withdrawn)));
+                println!("collected  {} SOL lifetime", display::sol(t.collected));
+                println!("withdrawn  {} SOL lifetime", display::sol(t.withdrawn));
+                Ok(())
+            }
+        },
+        Command::Referral(cmd) => match cmd {
+            ReferralCmd::Init => send(&rpc, &signer, ix::init_referrer_vault(&me)),
+            ReferralCmd::Withdraw => send(&rpc, &signer, ix::withdraw_referral_rewards(&me)),
+            ReferralCmd::Show => {
+                let v = accounts::fetch_referrer_vault(&rpc, &me)?
+                    .context("no referral vault for this keypair; run `punt referral init` first")?;
+                println!("vault      {}", pda::referrer_vault(&me));
+                println!("accrued    {} SOL", display::sol(v.accrued));
+                println!("earned     {} SOL lifetime", display::sol(v.total_earned));
+                Ok(())
+            }
+        },
+        Command::Meta(MetaCmd::Migrate) => {
+            // Migrating the meta only makes sense once no market of the old layout is left.
+            let legacy = accounts::fetch_legacy_markets(&rpc, Some(&me))?;
+            if !legacy.is_empty() {
+                bail!(
+                    "{} of your markets use the older layout and would be stranded; settle and close them \
+                     with the previous release first",
+                    legacy.len()
+                );
+            }
+            send(&rpc, &signer, ix::migrate_authority_meta(&me))
+        }
+        Command::Meta(MetaCmd::Init) => {
+            send(&rpc, &signer, ix::init_authority_meta(&me))?;
+            println!("authority meta {}", pda::authority_meta(&me));
+            Ok(())
+        }
+    }
+}
+
+fn create_market(rpc: &RpcClient, signer: &Keypair, args: CreateArgs) -> Result<()> {
+    let authority = signer.pubkey();
+    let cycle = match args.market_id {
+        Some(cycle) => cycle,
+        None => next_cycle(rpc, &authority)?,
+    };
+    let options = MarketOptions {
+        limits: BetLimits {
+            min_bet: args.min_bet.unwrap_or(0),
+            max_bet_per_ticket: args.max_bet.unwrap_or(0),
+            max_pool: args.max_pool.unwrap_or(0),
+        },
+        dispute: DisputeConfig {
+            window_secs: args.dispute_window_secs.unwrap_or(0),
+            bond: args.dispute_bond.unwrap_or(0),
+        },
+        quorum_threshold: args.quorum_threshold.unwrap_or(0),
+        oracle: None,
+        close_ts: args.close_ts.unwrap_or(0),
+        fixed_stake: args.fixed_stake.unwrap_or(0),
+        no_winner_policy: args.no_winner,
+        referral_bps: args.referral_bps.unwrap_or(0),
+        claim_window_secs: args.claim_window_secs.unwrap_or(0),
+        unclaimed_policy: args.unclaimed_to,
+    };
+    let options = (options != MarketOptions::default()).then_some(options);
+    let ix = ix::initialize_market(
+        &authority,
+        cycle,
+        args.title,
+        args.label_yes,
+        args.label_no,
+        args.fee_bps,
+        options,
+    );
+    send(rpc, signer, ix)?;
+    println!("market {} (cycle {cycle})", pda::market(&authority, cycle));
+    Ok(())
+}
+
+fn next_cycle(rpc: &RpcClient, authority: &Pubkey) -> Result<u64> {
+    let meta = accounts::fetch_authority_meta(rpc, authority)?
+        .context("no authority meta for this keypair; run `punt meta init` first")?;
+    Ok(meta.next_cycle)
+}
+
+fn show(key: &Pubkey, m: &BetMarket, schedule: Option<&FeeSchedule>) {
+    let (yes, no) = (accounts::fixed_str(&m.label_yes), accounts::fixed_str(&m.label_no));
+    println!("market     {key}");
+    println!("title      {}", accounts::fixed_str(&m.title));
+    println!("authority  {} (cycle {})", m.authority, m.cycle);
+    println!("status     {}", display::status(m, &yes, &no));
+    println!("pool       {} SOL", display::sol(m.pool_yes.saturating_add(m.pool_no)));
+    println!("  {}", display::odds_line(&yes, m.pool_yes, m.pool_no, m.bettors_yes));
+    println!("  {}", display::odds_line(&no, m.pool_no, m.pool_yes, m.bettors_no));
+    println!("fees       {} bps authority + {} bps host on profit", m.fee_bps, m.host_fee_bps);
+    if let Some(schedule) = schedule {
+        let n = schedule.recipient_count as usize;
+        for (i, (recipient, bps)) in schedule.recipients[..n].iter().zip(&schedule.weights).enumerate() {
+            let remainder = if i == schedule.remainder_index as usize { " (+ rounding)" } else { "" };
+            println!("  split    {recipient} {bps} bps of the authority share{remainder}");
+        }
+    }
+    if m.referral_bps > 0 {
+        println!("referrals  {} bps of referred tickets' fees", m.referral_bps);
+    }
+    match m.no_winner_policy {
+        NoWinnerPolicy::Fees => {}
+        NoWinnerPolicy::Refund => println!("no winner  stakes refunded"),
+        NoWinnerPolicy::Rollover => println!("no winner  pool rolls over to a later market"),
+    }
+    if m.bonus_pool > 0 {
+        println!("bonus      {} SOL rolled in for winners", display::sol(m.bonus_pool));
+    }
+    if m.min_bet > 0 || m.max_bet_per_ticket > 0 || m.max_pool > 0 {
+        println!(
+            "limits     min {} / ticket max {} / pool max {} SOL",
+            display::sol(m.min_bet),
+            display::sol(m.max_bet_per_ticket),
+            display::sol(m.max_pool)
+        );
+    }
+    if m.fixed_stake > 0 {
+        println!("stake      fixed {} SOL per ticket", display::sol(m.fixed_stake));
+    }
+    if m.close_ts > 0 {
+        println!("closes at  {}", m.close_ts);
+    }
+    if m.kind == MarketKind::Oracle {
+        println!("oracle     feed {} > {} at {}", m.oracle_feed, m.oracle_threshold, m.resolve_ts);
+    }
+    if m.resolved {
+        println!(
+            "resolution {:?}, confidence {} bps, resolved at {}",
+            m.resolution_source, m.confidence_bps, m.resolved_at
+        );
+        println!("claims     {} paid, {} SOL out", m.claimed_count, display::sol(m.claimed_total));
+        println!("accrued    {} SOL fees", display::sol(m.fees_accrued));
+        if m.treasury_paid > 0 {
+            println!("treasury   {} SOL host fees paid", display::sol(m.treasury_paid));
+        }
+        if m.claim_deadline > 0 {
+            let to = match m.unclaimed_policy {
+                UnclaimedPolicy::Treasury => "treasury",
+                UnclaimedPolicy::Authority => "authority",
+            };
+            let state = if m.unclaimed_swept { "swept" } else { "unclaimed go" };
+            println!("deadline   claims until {}, {state} to {to}", m.claim_deadline);
+        }
+        if m.rollover_pending > 0 {
+            println!("rollover   {} SOL waiting to roll over", display::sol(m.rollover_pending));
+        }
+    }
+    if m.prize_seed_hash != [0; 32] {
+        let weighting = match m.prize_weighting {
+            PrizeWeighting::PerTicket => "per ticket",
+            PrizeWeighting::ByStake => "by stake",
+        };
+        match m.prize_winner {
+            w if w == Pubkey::default() => println!("prize      committed, drawn {weighting}"),
+            w => println!("prize      won by {w} (drawn {weighting})"),
+        }
+    }
+}
+
+fn send(rpc: &RpcClient, signer: &Keypair, ix: Instruction) -> Result<()> {
+    let sig = rpc.send_and_confirm(&[ix], signer, &[])?;
+    println!("{sig}");
+    Ok(())
+}
+
+fn read_seed(path: &Path) -> Result<[u8; 32]> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(Sha256::digest(&bytes).into())
+}
+
+fn parse_outcome(s: &str) -> Result<u8> {
+    match s.to_ascii_lowercase().as_str() {
+        "void" => Ok(SIDE_VOID),
+        _ => parse_side(s),
+    }
+}
+
+fn parse_no_winner_policy(s: &str) -> Result<NoWinnerPolicy> {
+    match s.to_ascii_lowercase().as_str() {
+        "fees" => Ok(NoWinnerPolicy::Fees),
+        "refund" => Ok(NoWinnerPolicy::Refund),
+        "rollover" => Ok(NoWinnerPolicy::Rollover),
+        _ => bail!("no-winner policy must be fees, refund or rollover"),
+    }
+}
+
+fn parse_fee_share(s: &str) -> Result<(Pubkey, u16)> {
+    let (recipient, bps) = s.split_once(':').context("expected PUBKEY:BPS")?;
+    Ok((recipient.parse()?, bps.parse()?))
+}
+
+fn parse_unclaimed_policy(s: &str) -> Result<UnclaimedPolicy> {
+    match s.to_ascii_lowercase().as_str() {
+        "treasury" => Ok(UnclaimedPolicy::Treasury),
+        "authority" => Ok(UnclaimedPolicy::Authority),
+        _ => bail!("unclaimed payouts go to treasury or authority"),
+    }
+}
+
+fn parse_side(s: &str) -> Result<u8> {
+    match s.to_ascii_lowercase().as_str() {
+        "yes" | "0" => Ok(0),
+        "no" | "1" => Ok(1),
+        _ => bail!("side must be yes or no"),
+    }
+}
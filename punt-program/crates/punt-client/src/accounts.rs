@@ -0,0 +1,105 @@
+//! Decoding of program accounts fetched over RPC.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use punt_program::{
+    AuthorityMeta, BetMarket, BetTicket, DisputeBond, FeeSchedule, MarketTemplate, ReferrerVault, Treasury,
+};
+
+use crate::rpc::{MemcmpFilter, RpcClient};
+
+/// Decodes an Anchor account (discriminator checked).
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..]).map_err(|e| anyhow::anyhow!("decode failed: {e}"))
+}
+
+/// All `BetMarket` accounts owned by the program (accounts that fail to decode, e.g. older
+/// layouts, are skipped).
+pub fn fetch_markets(rpc: &RpcClient) -> Result<Vec<(Pubkey, BetMarket)>> {
+    fetch_all(rpc, BetMarket::DISCRIMINATOR, Vec::new())
+}
+
+/// All `BetTicket` accounts for `market`.
+pub fn fetch_tickets(rpc: &RpcClient, market: &Pubkey) -> Result<Vec<(Pubkey, BetTicket)>> {
+    // BetTicket layout: discriminator (8) + user (32) + market (32) ...
+    fetch_all(rpc, BetTicket::DISCRIMINATOR, vec![MemcmpFilter::new(8 + 32, market.as_ref())])
+}
+
+/// All unsettled `DisputeBond` accounts posted against `market`.
+pub fn fetch_bonds(rpc: &RpcClient, market: &Pubkey) -> Result<Vec<(Pubkey, DisputeBond)>> {
+    // DisputeBond layout: discriminator (8) + market (32) ...
+    fetch_all(rpc, DisputeBond::DISCRIMINATOR, vec![MemcmpFilter::new(8, market.as_ref())])
+}
+
+/// `BetMarket` accounts (of `authority`, or all) whose size is not the current layout's: markets
+/// of an earlier deployment (u16 ids, fewer fields), which this program version cannot read or
+/// settle. Their presence means the program was upgraded in place instead of freshly deployed.
+pub fn fetch_legacy_markets(rpc: &RpcClient, authority: Option<&Pubkey>) -> Result<Vec<Pubkey>> {
+    let mut filters = vec![MemcmpFilter::new(0, BetMarket::DISCRIMINATOR)];
+    if let Some(authority) = authority {
+        filters.push(MemcmpFilter::new(8, authority.as_ref()));
+    }
+    Ok(rpc
+        .get_program_accounts(&punt_program::ID, &filters)?
+        .into_iter()
+        .filter(|(_, data)| data.len() != 8 + BetMarket::SIZE)
+        .map(|(key, _)| key)
+        .collect())
+}
+
+pub fn fetch_market(rpc: &RpcClient, market: &Pubkey) -> Result<Option<BetMarket>> {
+    fetch_one(rpc, market)
+}
+
+/// All `MarketTemplate` accounts created by `authority`.
+pub fn fetch_templates(rpc: &RpcClient, authority: &Pubkey) -> Result<Vec<(Pubkey, MarketTemplate)>> {
+    // MarketTemplate layout: discriminator (8) + authority (32) ...
+    fetch_all(rpc, MarketTemplate::DISCRIMINATOR, vec![MemcmpFilter::new(8, authority.as_ref())])
+}
+
+pub fn fetch_ticket(rpc: &RpcClient, ticket: &Pubkey) -> Result<Option<BetTicket>> {
+    fetch_one(rpc, ticket)
+}
+
+pub fn fetch_authority_meta(rpc: &RpcClient, authority: &Pubkey) -> Result<Option<AuthorityMeta>> {
+    fetch_one(rpc, &crate::pda::authority_meta(authority))
+}
+
+pub fn fetch_treasury(rpc: &RpcClient) -> Result<Option<Treasury>> {
+    fetch_one(rpc, &crate::pda::treasury())
+}
+
+pub fn fetch_fee_schedule(rpc: &RpcClient, market: &Pubkey) -> Result<Option<FeeSchedule>> {
+    fetch_one(rpc, &crate::pda::fee_schedule(market))
+}
+
+pub fn fetch_referrer_vault(rpc: &RpcClient, referrer: &Pubkey) -> Result<Option<ReferrerVault>> {
+    fetch_one(rpc, &crate::pda::referrer_vault(referrer))
+}
+
+fn fetch_one<T: AccountDeserialize>(rpc: &RpcClient, key: &Pubkey) -> Result<Option<T>> {
+    match rpc.get_account_data(key)? {
+        Some(data) => Ok(Some(decode(&data).with_context(|| format!("account {key}"))?)),
+        None => Ok(None),
+    }
+}
+
+fn fetch_all<T: AccountDeserialize>(
+    rpc: &RpcClient,
+    discriminator: &[u8],
+    mut filters: Vec<MemcmpFilter>,
+) -> Result<Vec<(Pubkey, T)>> {
+    filters.insert(0, MemcmpFilter::new(0, discriminator));
+    Ok(rpc
+        .get_program_accounts(&punt_program::ID, &filters)?
+        .into_iter()
+        .filter_map(|(key, data)| decode(&data).ok().map(|acc| (key, acc)))
+        .collect())
+}
+
+/// Decodes a null-padded fixed-size text field (title / labels).
+pub fn fixed_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
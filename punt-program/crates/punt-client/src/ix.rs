@@ -0,0 +1,374 @@
+//! Instruction builders. Each returns a ready-to-sign `Instruction` using the program crate's
+//! generated `instruction::*` data and `accounts::*` metas.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_lang::system_program;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::solana_program::sysvar;
+use punt_program::{
+    accounts, instruction, BetLimits, BetTicket, FeeSchedule, MarketOptions, PrizeWeighting, ResolutionSource, ID,
+};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+pub fn init_authority_meta(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::InitAuthorityMeta {
+            authority: *authority,
+            authority_meta: pda::authority_meta(authority),
+            system_program: system_program::ID,
+        },
+        instruction::InitAuthorityMeta {},
+    )
+}
+
+/// Creates the market with id `cycle` (any id without an open market; `next_cycle` for sequential use).
+pub fn initialize_market(
+    authority: &Pubkey,
+    cycle: u64,
+    title: String,
+    label_yes: String,
+    label_no: String,
+    fee_bps: Option<u16>,
+    options: Option<MarketOptions>,
+) -> Instruction {
+    build(
+        accounts::InitializeMarket {
+            authority: *authority,
+            authority_meta: pda::authority_meta(authority),
+            market: pda::market(authority, cycle),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeMarket { cycle, title, label_yes, label_no, fee_bps, options },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_market_template(
+    authority: &Pubkey,
+    id: u16,
+    title: String,
+    label_yes: String,
+    label_no: String,
+    fee_bps: u16,
+    limits: BetLimits,
+    duration_secs: u32,
+) -> Instruction {
+    build(
+        accounts::CreateMarketTemplate {
+            authority: *authority,
+            authority_meta: pda::authority_meta(authority),
+            template: pda::template(authority, id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateMarketTemplate { id, title, label_yes, label_no, fee_bps, limits, duration_secs },
+    )
+}
+
+pub fn close_market_template(authority: &Pubkey, id: u16) -> Instruction {
+    build(
+        accounts::CloseMarketTemplate {
+            authority: *authority,
+            authority_meta: pda::authority_meta(authority),
+            template: pda::template(authority, id),
+        },
+        instruction::CloseMarketTemplate {},
+    )
+}
+
+/// Creates the market with id `cycle` from template `id`.
+pub fn initialize_market_from_template(authority: &Pubkey, id: u16, cycle: u64) -> Instruction {
+    build(
+        accounts::InitializeMarketFromTemplate {
+            authority: *authority,
+            authority_meta: pda::authority_meta(authority),
+            template: pda::template(authority, id),
+            market: pda::market(authority, cycle),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeMarketFromTemplate { cycle },
+    )
+}
+
+/// Rewrites a legacy (u16 `next_cycle`) authority meta in the current layout.
+pub fn migrate_authority_meta(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateAuthorityMeta {
+            authority: *authority,
+            authority_meta: pda::authority_meta(authority),
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAuthorityMeta {},
+    )
+}
+
+/// Opens `user`'s ticket; a `referrer` must already have a vault (`init_referrer_vault`).
+pub fn create_ticket(user: &Pubkey, market: &Pubkey, side: u8, referrer: Option<&Pubkey>) -> Instruction {
+    build(
+        accounts::CreateTicket {
+            user: *user,
+            market: *market,
+            ticket: pda::ticket(market, user),
+            referrer_vault: referrer.map(pda::referrer_vault),
+            system_program: system_program::ID,
+        },
+        instruction::CreateTicket { side, referrer: referrer.copied() },
+    )
+}
+
+pub fn place_bet(user: &Pubkey, market: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::PlaceBet {
+            user: *user,
+            market: *market,
+            ticket: pda::ticket(market, user),
+            system_program: system_program::ID,
+        },
+        instruction::PlaceBet { amount },
+    )
+}
+
+pub fn resolve_market(
+    resolver: &Pubkey,
+    market: &Pubkey,
+    winning_side: u8,
+    evidence_hash: [u8; 32],
+    confidence_bps: u16,
+    source: ResolutionSource,
+) -> Instruction {
+    build(
+        accounts::ResolveMarket { resolver: *resolver, market: *market },
+        instruction::ResolveMarket { winning_side, evidence_hash, confidence_bps, source },
+    )
+}
+
+pub fn freeze_market(authority: &Pubkey, market: &Pubkey) -> Instruction {
+    build(
+        accounts::FreezeMarket { authority: *authority, market: *market, slot_hashes: sysvar::slot_hashes::ID },
+        instruction::FreezeMarket {},
+    )
+}
+
+/// Keep a challenged result the arbiter did not rule on in time (permissionless).
+pub fn expire_dispute(market: &Pubkey) -> Instruction {
+    build(accounts::ExpireDispute { market: *market }, instruction::ExpireDispute {})
+}
+
+/// Return or slash `challenger`'s bond once the dispute is settled (permissionless; the bond's
+/// lamports and rent always go to `challenger` unless slashed).
+pub fn settle_dispute_bond(challenger: &Pubkey, market: &Pubkey) -> Instruction {
+    build(
+        accounts::SettleDisputeBond {
+            challenger: *challenger,
+            market: *market,
+            bond: pda::dispute_bond(market, challenger),
+        },
+        instruction::SettleDisputeBond {},
+    )
+}
+
+/// Void a market left unresolved past the resolution timeout (permissionless).
+pub fn void_stale_market(market: &Pubkey) -> Instruction {
+    build(accounts::VoidStaleMarket { market: *market }, instruction::VoidStaleMarket {})
+}
+
+/// Claim for `user`'s ticket; any fee payer may submit it, the payout always goes to `user`.
+/// `referrer` is the ticket's `referrer` field (`Pubkey::default()` when it has none).
+pub fn claim_winnings(user: &Pubkey, market: &Pubkey, referrer: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimWinnings {
+            user: *user,
+            market: *market,
+            ticket: pda::ticket(market, user),
+            referrer_vault: (*referrer != Pubkey::default()).then(|| pda::referrer_vault(referrer)),
+        },
+        instruction::ClaimWinnings {},
+    )
+}
+
+/// Close a settled ticket, returning its rent to `user`; any fee payer may submit it.
+pub fn close_ticket(user: &Pubkey, market: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseTicket { user: *user, market: *market, ticket: pda::ticket(market, user) },
+        instruction::CloseTicket {},
+    )
+}
+
+/// Close a ticket left behind by a closed market, returning its rent to `user`; any fee payer may
+/// submit it.
+pub fn close_orphan_ticket(user: &Pubkey, market: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseOrphanTicket { user: *user, market: *market, ticket: pda::ticket(market, user) },
+        instruction::CloseOrphanTicket {},
+    )
+}
+
+/// Splits the authority's fee share across `recipients` (bps weights summing to 10_000); must be
+/// sent before the first bet.
+pub fn set_fee_schedule(
+    authority: &Pubkey,
+    market: &Pubkey,
+    recipients: Vec<Pubkey>,
+    weights: Vec<u16>,
+    remainder_index: u8,
+) -> Instruction {
+    build(
+        accounts::SetFeeSchedule {
+            authority: *authority,
+            market: *market,
+            fee_schedule: pda::fee_schedule(market),
+            system_program: system_program::ID,
+        },
+        instruction::SetFeeSchedule { recipients, weights, remainder_index },
+    )
+}
+
+/// `caller` is the authority, or anyone once every payout is claimed. `schedule` is the market's
+/// fee schedule, required when `BetMarket::has_fee_schedule` is set.
+pub fn withdraw_fees(
+    caller: &Pubkey,
+    authority: &Pubkey,
+    market: &Pubkey,
+    schedule: Option<&FeeSchedule>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::WithdrawFees {
+            caller: *caller,
+            authority: *authority,
+            market: *market,
+            treasury: pda::treasury(),
+            fee_schedule: schedule.map(|_| pda::fee_schedule(market)),
+        },
+        instruction::WithdrawFees {},
+    );
+    if let Some(schedule) = schedule {
+        let recipients = &schedule.recipients[..schedule.recipient_count as usize];
+        ix.accounts.extend(recipients.iter().map(|r| AccountMeta::new(*r, false)));
+    }
+    ix
+}
+
+/// `caller` is the authority, or anyone once every payout is claimed (tipped from the rent).
+/// `schedule` is the market's fee schedule, if it has one: its recipients share any salvaged fees.
+pub fn close_market(caller: &Pubkey, authority: &Pubkey, market: &Pubkey, schedule: Option<&FeeSchedule>) -> Instruction {
+    let mut ix = build(
+        accounts::CloseMarket {
+            caller: *caller,
+            authority: *authority,
+            market: *market,
+            treasury: pda::treasury(),
+            fee_schedule: schedule.map(|_| pda::fee_schedule(market)),
+        },
+        instruction::CloseMarket {},
+    );
+    if let Some(schedule) = schedule {
+        let recipients = &schedule.recipients[..schedule.recipient_count as usize];
+        ix.accounts.extend(recipients.iter().map(|r| AccountMeta::new(*r, false)));
+    }
+    ix
+}
+
+/// After the claim deadline, moves unclaimed payouts out per the market's `UnclaimedPolicy`; any
+/// fee payer may send it.
+pub fn sweep_unclaimed(caller: &Pubkey, authority: &Pubkey, market: &Pubkey) -> Instruction {
+    build(
+        accounts::SweepUnclaimed {
+            caller: *caller,
+            market: *market,
+            authority: *authority,
+            treasury: pda::treasury(),
+        },
+        instruction::SweepUnclaimed {},
+    )
+}
+
+/// Moves `market`'s pending no-winner pool into the later market `destination`.
+pub fn roll_over_pool(authority: &Pubkey, market: &Pubkey, destination: &Pubkey) -> Instruction {
+    build(
+        accounts::RollOverPool { authority: *authority, market: *market, destination: *destination },
+        instruction::RollOverPool {},
+    )
+}
+
+/// Sends `market`'s pending no-winner pool to the treasury once its rollover deadline has passed.
+pub fn expire_rollover(market: &Pubkey) -> Instruction {
+    build(accounts::ExpireRollover { market: *market, treasury: pda::treasury() }, instruction::ExpireRollover {})
+}
+
+pub fn init_referrer_vault(referrer: &Pubkey) -> Instruction {
+    build(
+        accounts::InitReferrerVault {
+            referrer: *referrer,
+            referrer_vault: pda::referrer_vault(referrer),
+            system_program: system_program::ID,
+        },
+        instruction::InitReferrerVault {},
+    )
+}
+
+pub fn withdraw_referral_rewards(referrer: &Pubkey) -> Instruction {
+    build(
+        accounts::WithdrawReferralRewards { referrer: *referrer, referrer_vault: pda::referrer_vault(referrer) },
+        instruction::WithdrawReferralRewards {},
+    )
+}
+
+/// One-time creation of the platform treasury; any payer may send it.
+pub fn init_treasury(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::InitTreasury { payer: *payer, treasury: pda::treasury(), system_program: system_program::ID },
+        instruction::InitTreasury {},
+    )
+}
+
+/// Hands the treasury to `new_admin`; `admin` must be the current `Treasury::admin`.
+pub fn set_treasury_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::SetTreasuryAdmin { admin: *admin, treasury: pda::treasury() },
+        instruction::SetTreasuryAdmin { new_admin: *new_admin },
+    )
+}
+
+/// Pays `amount` of collected host fees to `destination`; `admin` must be the `Treasury::admin`.
+pub fn treasury_withdraw(admin: &Pubkey, destination: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::TreasuryWithdraw { admin: *admin, treasury: pda::treasury(), destination: *destination },
+        instruction::TreasuryWithdraw { amount },
+    )
+}
+
+/// Permissionless oracle resolution once `resolve_ts` has passed.
+pub fn resolve_from_feed(market: &Pubkey, feed: &Pubkey) -> Instruction {
+    build(accounts::ResolveFromFeed { market: *market, feed: *feed }, instruction::ResolveFromFeed {})
+}
+
+/// Commit `sha256(seed)` for a later prize draw; must be sent before the market is frozen.
+pub fn commit_prize_seed(authority: &Pubkey, market: &Pubkey, seed_hash: [u8; 32], weighting: PrizeWeighting) -> Instruction {
+    build(
+        accounts::CommitPrizeSeed { authority: *authority, market: *market },
+        instruction::CommitPrizeSeed { seed_hash, weighting },
+    )
+}
+
+/// Reveal `seed` and walk one page of the prize draw (anyone may). `tickets` is the next page of
+/// staked winning tickets, in entry order after the previous page; see `prize_draw_pages`.
+pub fn draw_prize_winner(market: &Pubkey, seed: [u8; 32], tickets: &[Pubkey]) -> Instruction {
+    let mut ix = build(accounts::DrawPrizeWinner { market: *market }, instruction::DrawPrizeWinner { seed });
+    ix.accounts.extend(tickets.iter().map(|t| AccountMeta::new_readonly(*t, false)));
+    ix
+}
+
+/// Every staked winning ticket sorted by `entry_index` and split into `draw_prize_winner` pages
+/// of at most `page` tickets.
+pub fn prize_draw_pages(tickets: &[(Pubkey, BetTicket)], page: usize) -> Vec<Vec<Pubkey>> {
+    let mut tickets = tickets.to_vec();
+    tickets.sort_by_key(|(_, t)| t.entry_index);
+    let keys: Vec<Pubkey> = tickets.into_iter().map(|(key, _)| key).collect();
+    keys.chunks(page.max(1)).map(<[Pubkey]>::to_vec).collect()
+}
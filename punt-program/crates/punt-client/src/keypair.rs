@@ -0,0 +1,20 @@
+//! Keypair loading in the Solana CLI JSON format (`[u8; 64]` array).
+
+use anyhow::{anyhow, Context, Result};
+use solana_keypair::Keypair;
+use std::path::Path;
+
+/// Reads a keypair file such as `~/.config/solana/id.json`. A leading `~/` is expanded.
+pub fn read_keypair(path: impl AsRef<Path>) -> Result<Keypair> {
+    let path = expand_home(path.as_ref());
+    let raw = std::fs::read_to_string(&path).with_context(|| format!("reading keypair {}", path.display()))?;
+    let bytes: Vec<u8> = serde_json::from_str(&raw).with_context(|| format!("parsing keypair {}", path.display()))?;
+    Keypair::try_from(bytes.as_slice()).map_err(|e| anyhow!("invalid keypair {}: {e}", path.display()))
+}
+
+pub(crate) fn expand_home(path: &Path) -> std::path::PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
@@ -0,0 +1,14 @@
+//! Off-chain companion to `punt-program`: a minimal blocking JSON-RPC client, PDA derivation,
+//! account decoding and instruction builders shared by the resolver, keeper and CLI binaries.
+//!
+//! Instruction data and account metas come straight from the program crate (built with
+//! `no-entrypoint`), so clients cannot drift from the on-chain interface.
+
+pub mod accounts;
+pub mod ix;
+pub mod keypair;
+pub mod pda;
+pub mod rpc;
+
+pub use punt_program::ID as PROGRAM_ID;
+pub use rpc::RpcClient;
@@ -0,0 +1,36 @@
+//! Program-derived addresses, mirroring the `seeds = [...]` constraints in punt-program.
+
+use anchor_lang::prelude::Pubkey;
+use punt_program::ID;
+
+pub fn authority_meta(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"authority_meta", authority.as_ref()], &ID).0
+}
+
+pub fn market(authority: &Pubkey, cycle: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"market", authority.as_ref(), &cycle.to_le_bytes()], &ID).0
+}
+
+pub fn ticket(market: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"ticket", market.as_ref(), user.as_ref()], &ID).0
+}
+
+pub fn referrer_vault(referrer: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"referrer_vault", referrer.as_ref()], &ID).0
+}
+
+pub fn treasury() -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury"], &ID).0
+}
+
+pub fn dispute_bond(market: &Pubkey, challenger: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bond", market.as_ref(), challenger.as_ref()], &ID).0
+}
+
+pub fn fee_schedule(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_schedule", market.as_ref()], &ID).0
+}
+
+pub fn template(authority: &Pubkey, id: u16) -> Pubkey {
+    Pubkey::find_program_address(&[b"template", authority_meta(authority).as_ref(), &id.to_le_bytes()], &ID).0
+}
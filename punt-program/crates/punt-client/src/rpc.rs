@@ -0,0 +1,141 @@
+//! Minimal blocking Solana JSON-RPC client (only the calls punt tooling needs).
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_POLL: Duration = Duration::from_millis(500);
+
+/// `memcmp` filter for `getProgramAccounts`.
+#[derive(Clone, Debug)]
+pub struct MemcmpFilter {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl MemcmpFilter {
+    pub fn new(offset: usize, bytes: &[u8]) -> Self {
+        Self { offset, bytes: bytes.to_vec() }
+    }
+}
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build();
+        Self { url: url.into(), agent }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let resp: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(body)
+            .with_context(|| format!("{method} request to {}", self.url))?
+            .into_json()
+            .with_context(|| format!("{method} response"))?;
+        if let Some(err) = resp.get("error") {
+            bail!("{method} failed: {err}");
+        }
+        let result = resp.get("result").cloned().ok_or_else(|| anyhow!("{method}: missing result"))?;
+        serde_json::from_value(result).with_context(|| format!("{method}: unexpected result shape"))
+    }
+
+    /// Raw account data, or `None` if the account does not exist.
+    pub fn get_account_data(&self, key: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let resp: Value = self.call(
+            "getAccountInfo",
+            json!([key.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        match resp.get("value") {
+            Some(Value::Null) | None => Ok(None),
+            Some(v) => Ok(Some(decode_data(&v["data"])?)),
+        }
+    }
+
+    pub fn get_balance(&self, key: &Pubkey) -> Result<u64> {
+        let resp: Value = self.call("getBalance", json!([key.to_string(), { "commitment": "confirmed" }]))?;
+        resp["value"].as_u64().ok_or_else(|| anyhow!("getBalance: missing value"))
+    }
+
+    pub fn get_program_accounts(&self, program: &Pubkey, filters: &[MemcmpFilter]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let filters: Vec<Value> = filters
+            .iter()
+            .map(|f| json!({ "memcmp": { "offset": f.offset, "bytes": B64.encode(&f.bytes), "encoding": "base64" } }))
+            .collect();
+        let resp: Vec<Value> = self.call(
+            "getProgramAccounts",
+            json!([program.to_string(), { "encoding": "base64", "commitment": "confirmed", "filters": filters }]),
+        )?;
+        resp.iter()
+            .map(|entry| {
+                let key = entry["pubkey"].as_str().ok_or_else(|| anyhow!("getProgramAccounts: missing pubkey"))?;
+                let key = Pubkey::from_str(key).map_err(|e| anyhow!("bad pubkey {key}: {e}"))?;
+                Ok((key, decode_data(&entry["account"]["data"])?))
+            })
+            .collect()
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let resp: Value = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let hash = resp["value"]["blockhash"].as_str().ok_or_else(|| anyhow!("getLatestBlockhash: missing blockhash"))?;
+        Hash::from_str(hash).map_err(|e| anyhow!("bad blockhash {hash}: {e:?}"))
+    }
+
+    /// Signs `instructions` with `payer` (+ extra `signers`), sends, and waits for confirmation.
+    pub fn send_and_confirm(&self, instructions: &[Instruction], payer: &Keypair, signers: &[&Keypair]) -> Result<String> {
+        let blockhash = self.get_latest_blockhash()?;
+        let mut all: Vec<&Keypair> = vec![payer];
+        all.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &all, blockhash);
+        let wire = bincode::serialize(&tx).context("serializing transaction")?;
+        let sig: String = self.call(
+            "sendTransaction",
+            json!([B64.encode(wire), { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        self.confirm(&sig)?;
+        Ok(sig)
+    }
+
+    fn confirm(&self, sig: &str) -> Result<()> {
+        let start = Instant::now();
+        while start.elapsed() < CONFIRM_TIMEOUT {
+            let resp: Value = self.call("getSignatureStatuses", json!([[sig]]))?;
+            if let Some(status) = resp["value"][0].as_object() {
+                if !status["err"].is_null() {
+                    bail!("transaction {sig} failed: {}", status["err"]);
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(());
+                }
+            }
+            std::thread::sleep(CONFIRM_POLL);
+        }
+        bail!("transaction {sig} not confirmed within {CONFIRM_TIMEOUT:?}")
+    }
+}
+
+fn decode_data(data: &Value) -> Result<Vec<u8>> {
+    let encoded = data[0].as_str().ok_or_else(|| anyhow!("account data not base64-encoded"))?;
+    B64.decode(encoded).context("decoding account data")
+}
@@ -0,0 +1,70 @@
+//! Persistent per-market progress so a restarted keeper resumes where it left off and backs off
+//! from markets whose last action failed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    #[serde(skip)]
+    path: PathBuf,
+    pub markets: BTreeMap<String, MarketProgress>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MarketProgress {
+    pub frozen: bool,
+    pub resolved: bool,
+    pub claims_sent: u32,
+    pub tickets_closed: u32,
+    #[serde(default)] // absent in checkpoints written before claim deadlines
+    pub swept: bool,
+    pub fees_withdrawn: bool,
+    pub closed: bool,
+    #[serde(default)] // tickets left on the closed market have been cleaned up
+    pub orphans_cleared: bool,
+    pub failures: u32,
+    pub next_attempt_ts: i64,
+    pub last_error: Option<String>,
+}
+
+impl Checkpoint {
+    /// Loads the checkpoint at `path`, or starts empty if it does not exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut cp = match std::fs::read_to_string(path) {
+            Ok(raw) => serde_json::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Checkpoint::default(),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        cp.path = path.to_path_buf();
+        Ok(cp)
+    }
+
+    /// Writes atomically (temp file + rename) so a crash never leaves a truncated checkpoint.
+    pub fn save(&self) -> Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?).with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path).with_context(|| format!("replacing {}", self.path.display()))
+    }
+
+    pub fn market(&mut self, key: &str) -> &mut MarketProgress {
+        self.markets.entry(key.to_string()).or_default()
+    }
+}
+
+impl MarketProgress {
+    pub fn record_failure(&mut self, now: i64, backoff_secs: i64, err: String) {
+        self.failures = self.failures.saturating_add(1);
+        let shift = self.failures.min(10) - 1;
+        self.next_attempt_ts = now.saturating_add(backoff_secs.saturating_mul(1 << shift));
+        self.last_error = Some(err);
+    }
+
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.next_attempt_ts = 0;
+        self.last_error = None;
+    }
+}
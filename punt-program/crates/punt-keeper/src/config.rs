@@ -0,0 +1,64 @@
+//! `keeper.toml` settings (see `keeper.example.toml`).
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub rpc_url: String,
+    pub payer_keypair: PathBuf,
+    #[serde(default)]
+    pub authority_keypairs: Vec<PathBuf>,
+    #[serde(default)]
+    pub all_markets: bool,
+    pub checkpoint: PathBuf,
+    #[serde(default = "default_poll_secs")]
+    pub poll_secs: u64,
+    pub policy: Policy,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Policy {
+    #[serde(default = "yes")]
+    pub freeze_at_close: bool,
+    #[serde(default = "yes")]
+    pub resolve_oracle: bool,
+    #[serde(default = "yes")]
+    pub void_stale: bool,
+    #[serde(default = "yes")]
+    pub claim_winners: bool,
+    #[serde(default = "yes")]
+    pub close_losing_tickets: bool,
+    #[serde(default = "yes")]
+    pub withdraw_fees: bool,
+    #[serde(default = "yes")]
+    pub close_markets: bool,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    #[serde(default = "default_retry_backoff_secs")]
+    pub retry_backoff_secs: i64,
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn default_poll_secs() -> u64 {
+    10
+}
+
+fn default_batch_size() -> usize {
+    4
+}
+
+fn default_retry_backoff_secs() -> i64 {
+    30
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))
+    }
+}
@@ -0,0 +1,260 @@
+//! punt-keeper: cranks the market lifecycle so nothing lingers on chain. Each pass scans every
+//! `BetMarket`, plans the due actions (see `plan`) and submits them:
+//!
+//! - freeze standard markets once their `close_ts` passes (authority key required),
+//! - resolve oracle markets from their feed once `resolve_ts` passes (permissionless),
+//! - void standard markets left unresolved past the resolution timeout (permissionless),
+//! - keep challenged results the arbiter did not rule on in time (permissionless),
+//! - settle challenge bonds once their dispute is over, before the market closes (permissionless),
+//! - pay out winning tickets and close losing ones in batches (permissionless),
+//! - sweep payouts left unclaimed past the claim deadline and close those tickets (permissionless),
+//! - withdraw fees and close the market once every ticket is settled (permissionless; the authority
+//!   still receives the fees and rent, the keeper a small close tip),
+//! - close tickets left behind on markets that have already been closed (permissionless).
+//!
+//! Progress and per-market retry backoff are persisted to the checkpoint file after every pass.
+
+mod checkpoint;
+mod config;
+mod plan;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{bail, Context, Result};
+use checkpoint::{Checkpoint, MarketProgress};
+use clap::Parser;
+use config::Config;
+use plan::Action;
+use punt_client::{accounts, ix, keypair, RpcClient};
+use punt_program::{BetMarket, FeeSchedule};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Parser)]
+#[command(about = "Crank punt markets through freeze, resolve, claim, fee withdrawal and close")]
+struct Args {
+    /// Path to keeper.toml
+    #[arg(long, default_value = "keeper.toml")]
+    config: PathBuf,
+    /// Run a single pass and exit instead of polling.
+    #[arg(long)]
+    once: bool,
+    /// Log planned actions without sending transactions or updating the checkpoint.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+struct Keeper {
+    rpc: RpcClient,
+    payer: Keypair,
+    authorities: HashMap<Pubkey, Keypair>,
+    cfg: Config,
+    dry_run: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let cfg = Config::load(&args.config)?;
+    let payer = keypair::read_keypair(&cfg.payer_keypair)?;
+    let authorities = cfg
+        .authority_keypairs
+        .iter()
+        .map(|p| keypair::read_keypair(p).map(|kp| (kp.pubkey(), kp)))
+        .collect::<Result<HashMap<_, _>>>()?;
+    let mut checkpoint = Checkpoint::load(&cfg.checkpoint)?;
+    let rpc = RpcClient::new(&cfg.rpc_url);
+    // Older-layout markets hold escrow this version can never pay out; refuse to run rather than
+    // crank around them (see "Upgrading the program" in the README).
+    let legacy = accounts::fetch_legacy_markets(&rpc, None)?;
+    if let Some(first) = legacy.first() {
+        bail!(
+            "{} markets (e.g. {first}) use an older BetMarket layout; settle them with the old release and \
+             deploy this version fresh",
+            legacy.len()
+        );
+    }
+    let keeper = Keeper { rpc, payer, authorities, cfg, dry_run: args.dry_run };
+    println!(
+        "[keeper] payer {} for {} authorities on {}{}",
+        keeper.payer.pubkey(),
+        keeper.authorities.len(),
+        keeper.rpc.url(),
+        if keeper.dry_run { " (dry run)" } else { "" }
+    );
+
+    loop {
+        if let Err(e) = keeper.pass(&mut checkpoint) {
+            eprintln!("[keeper] pass failed: {e:#}");
+        }
+        if !keeper.dry_run {
+            checkpoint.save()?;
+        }
+        if args.once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(keeper.cfg.poll_secs));
+    }
+}
+
+impl Keeper {
+    fn pass(&self, checkpoint: &mut Checkpoint) -> Result<()> {
+        let now = unix_now();
+        let markets = accounts::fetch_markets(&self.rpc)?;
+        let live: HashSet<Pubkey> = markets.iter().map(|(key, _)| *key).collect();
+        for (key, market) in markets {
+            let can_sign = self.authorities.contains_key(&market.authority);
+            if !self.cfg.all_markets && !can_sign {
+                continue;
+            }
+            let progress = checkpoint.market(&key.to_string());
+            if progress.closed || now < progress.next_attempt_ts {
+                continue;
+            }
+            // Tickets only matter once claims can go through; skip the scan otherwise.
+            let tickets = if market.is_final(now) {
+                accounts::fetch_tickets(&self.rpc, &key)?.into_iter().map(|(_, t)| t).collect()
+            } else {
+                Vec::new()
+            };
+            for action in plan::plan(&market, &tickets, now, &self.cfg.policy, can_sign) {
+                if self.dry_run {
+                    println!("[keeper] {key}: would {}", describe(&action));
+                    continue;
+                }
+                match self.execute(&key, &market, &action) {
+                    Ok(sig) => {
+                        println!("[keeper] {key}: {} ({sig})", describe(&action));
+                        record(progress, &action);
+                        progress.record_success();
+                    }
+                    Err(e) => {
+                        eprintln!("[keeper] {key}: {} failed: {e:#}", describe(&action));
+                        progress.record_failure(now, self.cfg.policy.retry_backoff_secs, format!("{e:#}"));
+                        break; // later actions depend on this one; retry after backoff
+                    }
+                }
+            }
+        }
+        self.close_orphans(checkpoint, &live)
+    }
+
+    /// Closes tickets left on markets that have disappeared since an earlier pass: those this
+    /// keeper closed itself, or with `all_markets` any market it has tracked (a closed market no
+    /// longer names its authority). Each gone market's tickets are fetched once; after a clean run
+    /// it is marked `orphans_cleared` and never scanned again.
+    fn close_orphans(&self, checkpoint: &mut Checkpoint, live: &HashSet<Pubkey>) -> Result<()> {
+        if !self.cfg.policy.close_losing_tickets {
+            return Ok(());
+        }
+        let gone: Vec<Pubkey> = checkpoint
+            .markets
+            .iter()
+            .filter(|(_, p)| !p.orphans_cleared && (self.cfg.all_markets || p.closed))
+            .filter_map(|(key, _)| key.parse().ok())
+            .filter(|market| !live.contains(market))
+            .collect();
+        for market in gone {
+            // Markets that merely failed to decode (older layouts) still exist and are left alone.
+            if self.rpc.get_account_data(&market)?.is_some() {
+                continue;
+            }
+            let tickets: Vec<_> = accounts::fetch_tickets(&self.rpc, &market)?.into_iter().map(|(_, t)| t).collect();
+            let mut cleared = true;
+            for batch in plan::orphans(&tickets, &HashSet::from([market]), &self.cfg.policy) {
+                if self.dry_run {
+                    println!("[keeper] {market}: would close {} orphaned tickets", batch.len());
+                    continue;
+                }
+                let ixs: Vec<Instruction> = batch.iter().map(|(m, u)| ix::close_orphan_ticket(u, m)).collect();
+                match self.rpc.send_and_confirm(&ixs, &self.payer, &[]) {
+                    Ok(sig) => println!("[keeper] {market}: closed {} orphaned tickets ({sig})", batch.len()),
+                    Err(e) => {
+                        eprintln!("[keeper] {market}: closing {} orphaned tickets failed: {e:#}", batch.len());
+                        cleared = false;
+                    }
+                }
+            }
+            checkpoint.market(&market.to_string()).orphans_cleared = cleared;
+        }
+        Ok(())
+    }
+
+    fn fee_schedule(&self, key: &Pubkey, market: &BetMarket) -> Result<Option<FeeSchedule>> {
+        if !market.has_fee_schedule {
+            return Ok(None);
+        }
+        Ok(Some(accounts::fetch_fee_schedule(&self.rpc, key)?.context("fee schedule missing")?))
+    }
+
+    fn execute(&self, key: &Pubkey, market: &BetMarket, action: &Action) -> Result<String> {
+        let authority = &market.authority;
+        let (ixs, needs_authority): (Vec<Instruction>, bool) = match action {
+            Action::Freeze => (vec![ix::freeze_market(authority, key)], true),
+            Action::ResolveFromFeed => (vec![ix::resolve_from_feed(key, &market.oracle_feed)], false),
+            Action::VoidStale => (vec![ix::void_stale_market(key)], false),
+            Action::ExpireDispute => (vec![ix::expire_dispute(key)], false),
+            Action::SettleBonds => {
+                // One batch per pass; `open_bonds` keeps the action planned until none are left.
+                let bonds = accounts::fetch_bonds(&self.rpc, key)?;
+                let batch = bonds.iter().take(self.cfg.policy.batch_size.max(1));
+                (batch.map(|(_, b)| ix::settle_dispute_bond(&b.challenger, key)).collect(), false)
+            }
+            Action::Claim(tickets) => (tickets.iter().map(|(u, r)| ix::claim_winnings(u, key, r)).collect(), false),
+            Action::CloseTickets(users) => (users.iter().map(|u| ix::close_ticket(u, key)).collect(), false),
+            Action::SweepUnclaimed => (vec![ix::sweep_unclaimed(&self.payer.pubkey(), authority, key)], false),
+            Action::ExpireRollover => (vec![ix::expire_rollover(key)], false),
+            Action::WithdrawFees => {
+                let schedule = self.fee_schedule(key, market)?;
+                (vec![ix::withdraw_fees(&self.payer.pubkey(), authority, key, schedule.as_ref())], false)
+            }
+            Action::CloseMarket => {
+                let schedule = self.fee_schedule(key, market)?;
+                (vec![ix::close_market(&self.payer.pubkey(), authority, key, schedule.as_ref())], false)
+            }
+        };
+        if needs_authority {
+            let signer = self.authorities.get(authority).context("authority key not loaded")?;
+            self.rpc.send_and_confirm(&ixs, &self.payer, &[signer])
+        } else {
+            self.rpc.send_and_confirm(&ixs, &self.payer, &[])
+        }
+    }
+}
+
+fn record(progress: &mut MarketProgress, action: &Action) {
+    match action {
+        Action::Freeze => progress.frozen = true,
+        Action::ExpireDispute | Action::SettleBonds | Action::ExpireRollover => {}
+        Action::ResolveFromFeed | Action::VoidStale => progress.resolved = true,
+        Action::Claim(users) => progress.claims_sent = progress.claims_sent.saturating_add(users.len() as u32),
+        Action::CloseTickets(users) => {
+            progress.tickets_closed = progress.tickets_closed.saturating_add(users.len() as u32)
+        }
+        Action::SweepUnclaimed => progress.swept = true,
+        Action::WithdrawFees => progress.fees_withdrawn = true,
+        Action::CloseMarket => progress.closed = true,
+    }
+}
+
+fn describe(action: &Action) -> String {
+    match action {
+        Action::Freeze => "freeze".into(),
+        Action::ResolveFromFeed => "resolve from feed".into(),
+        Action::VoidStale => "void stale market".into(),
+        Action::ExpireDispute => "expire unruled dispute".into(),
+        Action::SettleBonds => "settle dispute bonds".into(),
+        Action::Claim(users) => format!("claim {} tickets", users.len()),
+        Action::CloseTickets(users) => format!("close {} losing tickets", users.len()),
+        Action::SweepUnclaimed => "sweep unclaimed payouts".into(),
+        Action::ExpireRollover => "send expired rollover to treasury".into(),
+        Action::WithdrawFees => "withdraw fees".into(),
+        Action::CloseMarket => "close market".into(),
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
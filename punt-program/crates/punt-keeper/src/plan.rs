@@ -0,0 +1,357 @@
+//! Pure lifecycle planning: given a market, its open tickets and the clock, decide which
+//! cranks are due. Kept free of I/O so the policy is unit-testable.
+
+use anchor_lang::prelude::Pubkey;
+use punt_program::{BetMarket, BetTicket, MarketKind, DISPUTE_CHALLENGED};
+use std::collections::HashSet;
+
+use crate::config::Policy;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Freeze,
+    ResolveFromFeed,
+    /// Refund every bet of a market left unresolved past the resolution timeout.
+    VoidStale,
+    /// Keep a challenged result the arbiter did not rule on before the arbitration deadline.
+    ExpireDispute,
+    /// Return (or slash) every challenge bond once the dispute is settled.
+    SettleBonds,
+    /// Claim for these `(owner, referrer)` tickets (one transaction).
+    Claim(Vec<(Pubkey, Pubkey)>),
+    /// Close these losing (or expired) tickets, returning rent to their owners (one transaction).
+    CloseTickets(Vec<Pubkey>),
+    /// Move payouts left unclaimed past the claim deadline out of escrow.
+    SweepUnclaimed,
+    /// Send a no-winner pool the authority never rolled over to the treasury after the rollover deadline.
+    ExpireRollover,
+    WithdrawFees,
+    CloseMarket,
+}
+
+/// Actions due for `market`, in execution order. `can_sign` says whether the keeper holds the
+/// market authority's key (needed for freeze; fee withdrawal and close are permissionless once
+/// every payout has been claimed).
+pub fn plan(market: &BetMarket, tickets: &[BetTicket], now: i64, policy: &Policy, can_sign: bool) -> Vec<Action> {
+    let mut actions = Vec::new();
+    if !market.resolved {
+        if market.kind == MarketKind::Oracle {
+            if policy.resolve_oracle && now >= market.resolve_ts {
+                actions.push(Action::ResolveFromFeed);
+            }
+        } else if policy.void_stale && market.is_stale(now) {
+            actions.push(Action::VoidStale);
+        } else if policy.freeze_at_close && can_sign && !market.frozen && market.close_ts > 0 && now >= market.close_ts {
+            actions.push(Action::Freeze);
+        }
+        return actions;
+    }
+    if !market.is_final(now) {
+        if market.dispute_state == DISPUTE_CHALLENGED && now >= market.arbitration_deadline() {
+            actions.push(Action::ExpireDispute);
+        }
+        return actions;
+    }
+
+    // Bonds need the market to settle, so they go before it can close.
+    if policy.close_markets && market.open_bonds > 0 {
+        actions.push(Action::SettleBonds);
+    }
+
+    let refund = market.refunds_stakes();
+    // Past the claim deadline unclaimed payouts are swept and every open ticket is closed like a loser.
+    let expired = market.claims_expired(now);
+    let batch = policy.batch_size.max(1);
+    // Zero-stake tickets are owed nothing, so they are closed like losers whatever their side.
+    let (winners, losers): (Vec<&BetTicket>, Vec<&BetTicket>) = tickets
+        .iter()
+        .filter(|t| !t.claimed)
+        .partition(|t| !expired && t.amount > 0 && (refund || t.side == market.winning_side));
+    if expired && !market.all_claimed() {
+        actions.push(Action::SweepUnclaimed);
+    }
+
+    // Claims close winning tickets, so they wait while a committed prize draw is still owed.
+    let claim = policy.claim_winners && !market.prize_draw_pending(now);
+    if claim {
+        for chunk in winners.chunks(batch) {
+            actions.push(Action::Claim(chunk.iter().map(|t| (t.user, t.referrer)).collect()));
+        }
+    }
+    if policy.close_losing_tickets {
+        for chunk in losers.chunks(batch) {
+            actions.push(Action::CloseTickets(chunk.iter().map(|t| t.user).collect()));
+        }
+    }
+    // Fees and close only once every winner has been paid; losing tickets are closed first so
+    // their rent is not stranded behind a closed market.
+    let winners_left = !(claim || winners.is_empty());
+    let losers_left = !(policy.close_losing_tickets || losers.is_empty());
+    if !winners_left {
+        if policy.withdraw_fees && market.fees_accrued > 0 {
+            actions.push(Action::WithdrawFees);
+        }
+        let fees_left = !(policy.withdraw_fees || market.fees_accrued == 0);
+        // A pending rollover needs the authority to pick the destination market, so it blocks close
+        // until the rollover deadline, after which it goes to the treasury.
+        let expire_rollover = policy.close_markets && market.rollover_pending > 0 && now >= market.rollover_deadline();
+        if expire_rollover {
+            actions.push(Action::ExpireRollover);
+        }
+        let rollover_left = market.rollover_pending > 0 && !expire_rollover;
+        // A slashed bond adds to the fees, so close waits for the pass after the bonds settle.
+        if policy.close_markets && !losers_left && !fees_left && !rollover_left && market.open_bonds == 0 {
+            actions.push(Action::CloseMarket);
+        }
+    }
+    actions
+}
+
+/// Tickets left behind on markets in `closed`, as `(market, owner)` batches for
+/// `close_orphan_ticket` (one transaction each).
+pub fn orphans(tickets: &[BetTicket], closed: &HashSet<Pubkey>, policy: &Policy) -> Vec<Vec<(Pubkey, Pubkey)>> {
+    if !policy.close_losing_tickets {
+        return Vec::new();
+    }
+    let left: Vec<(Pubkey, Pubkey)> =
+        tickets.iter().filter(|t| closed.contains(&t.market)).map(|t| (t.market, t.user)).collect();
+    left.chunks(policy.batch_size.max(1)).map(<[_]>::to_vec).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use punt_program::{DISPUTE_NONE, ROLLOVER_TIMEOUT_SECS};
+
+    fn policy() -> Policy {
+        Policy {
+            freeze_at_close: true,
+            resolve_oracle: true,
+            void_stale: true,
+            claim_winners: true,
+            close_losing_tickets: true,
+            withdraw_fees: true,
+            close_markets: true,
+            batch_size: 2,
+            retry_backoff_secs: 30,
+        }
+    }
+
+    fn market() -> BetMarket {
+        BetMarket {
+            authority: Pubkey::new_unique(),
+            cycle: 0,
+            pool_yes: 0,
+            pool_no: 0,
+            resolved: false,
+            frozen: false,
+            fee_bps: 20,
+            host_fee_bps: 670,
+            bump: 255,
+            winning_side: 255,
+            fees_accrued: 0,
+            title: [0; 64],
+            label_yes: [0; 32],
+            label_no: [0; 32],
+            bettors_yes: 0,
+            bettors_no: 0,
+            claimed_total: 0,
+            claimed_count: 0,
+            min_bet: 0,
+            max_bet_per_ticket: 0,
+            max_pool: 0,
+            reopen_count: 0,
+            resolved_at: 0,
+            dispute_window_secs: 0,
+            dispute_bond: 0,
+            dispute_state: DISPUTE_NONE,
+            challenge_count: 0,
+            quorum_threshold: 0,
+            evidence_hash: [0; 32],
+            confidence_bps: 0,
+            resolution_source: Default::default(),
+            kind: MarketKind::Standard,
+            oracle_feed: Pubkey::default(),
+            resolve_ts: 0,
+            oracle_threshold: 0,
+            close_ts: 0,
+            fixed_stake: 0,
+            prize_seed_hash: [0; 32],
+            prize_weighting: Default::default(),
+            prize_winner: Pubkey::default(),
+            no_winner_policy: Default::default(),
+            bonus_pool: 0,
+            rollover_pending: 0,
+            referral_bps: 0,
+            has_fee_schedule: false,
+            treasury_paid: 0,
+            claim_window_secs: 0,
+            claim_deadline: 0,
+            unclaimed_policy: Default::default(),
+            unclaimed_swept: false,
+            frozen_at: 0,
+            prize_entropy: [0; 32],
+            prize_cursor: Pubkey::default(),
+            prize_counted: 0,
+            prize_staked: 0,
+            prize_candidate: Pubkey::default(),
+            open_bonds: 0,
+            serial: 1,
+        }
+    }
+
+    fn ticket(side: u8) -> BetTicket {
+        BetTicket {
+            user: Pubkey::new_unique(),
+            market: Pubkey::default(),
+            side,
+            amount: 10,
+            claimed: false,
+            bump: 255,
+            referrer: Pubkey::default(),
+            entry_index: 0,
+            market_serial: 1,
+        }
+    }
+
+    #[test]
+    fn freezes_after_close_time_only_with_authority_key() {
+        let m = BetMarket { close_ts: 100, ..market() };
+        assert_eq!(plan(&m, &[], 99, &policy(), true), vec![]);
+        assert_eq!(plan(&m, &[], 100, &policy(), true), vec![Action::Freeze]);
+        assert_eq!(plan(&m, &[], 100, &policy(), false), vec![]);
+    }
+
+    #[test]
+    fn voids_markets_left_unresolved_past_the_timeout() {
+        use punt_program::RESOLUTION_TIMEOUT_SECS;
+        let m = BetMarket { frozen: true, frozen_at: 100, ..market() };
+        assert_eq!(plan(&m, &[], 100 + RESOLUTION_TIMEOUT_SECS - 1, &policy(), true), vec![]);
+        assert_eq!(plan(&m, &[], 100 + RESOLUTION_TIMEOUT_SECS, &policy(), false), vec![Action::VoidStale]);
+        // Never frozen: the timeout runs from close_ts.
+        let m = BetMarket { close_ts: 100, ..market() };
+        assert_eq!(plan(&m, &[], 100 + RESOLUTION_TIMEOUT_SECS, &policy(), false), vec![Action::VoidStale]);
+    }
+
+    #[test]
+    fn settles_resolved_market_in_order() {
+        let m = BetMarket { resolved: true, frozen: true, winning_side: 0, fees_accrued: 5, ..market() };
+        let tickets = [ticket(0), ticket(0), ticket(0), ticket(1)];
+        let actions = plan(&m, &tickets, 0, &policy(), true);
+        assert_eq!(actions.len(), 5);
+        assert!(matches!(&actions[0], Action::Claim(users) if users.len() == 2));
+        assert!(matches!(&actions[1], Action::Claim(users) if users.len() == 1));
+        assert!(matches!(&actions[2], Action::CloseTickets(users) if users.len() == 1));
+        assert_eq!(actions[3..], [Action::WithdrawFees, Action::CloseMarket]);
+    }
+
+    #[test]
+    fn waits_for_dispute_window() {
+        let m = BetMarket { resolved: true, winning_side: 0, resolved_at: 100, dispute_window_secs: 60, ..market() };
+        assert_eq!(plan(&m, &[ticket(0)], 159, &policy(), true), vec![]);
+        assert!(!plan(&m, &[ticket(0)], 160, &policy(), true).is_empty());
+    }
+
+    #[test]
+    fn expires_disputes_the_arbiter_left_unruled() {
+        use punt_program::ARBITRATION_WINDOW_SECS;
+        let m = BetMarket { resolved: true, winning_side: 0, resolved_at: 100, dispute_window_secs: 60, dispute_state: DISPUTE_CHALLENGED, ..market() };
+        let deadline = 160 + ARBITRATION_WINDOW_SECS;
+        assert_eq!(plan(&m, &[ticket(0)], deadline - 1, &policy(), false), vec![]);
+        assert_eq!(plan(&m, &[ticket(0)], deadline, &policy(), false), vec![Action::ExpireDispute]);
+    }
+
+    #[test]
+    fn settles_bonds_before_closing() {
+        use punt_program::DISPUTE_UPHELD;
+        let m = BetMarket { resolved: true, winning_side: 0, dispute_state: DISPUTE_UPHELD, open_bonds: 2, ..market() };
+        assert_eq!(plan(&m, &[], 0, &policy(), false), vec![Action::SettleBonds]);
+        let settled = BetMarket { open_bonds: 0, fees_accrued: 5, ..m };
+        assert_eq!(plan(&settled, &[], 0, &policy(), false), vec![Action::WithdrawFees, Action::CloseMarket]);
+    }
+
+    #[test]
+    fn closes_zero_stake_tickets_instead_of_claiming() {
+        let m = BetMarket { resolved: true, winning_side: 0, pool_no: 10, ..market() };
+        let empty = [BetTicket { amount: 0, ..ticket(0) }];
+        let actions = plan(&m, &empty, 0, &policy(), false);
+        assert_eq!(actions, vec![Action::CloseTickets(vec![empty[0].user]), Action::CloseMarket]);
+    }
+
+    #[test]
+    fn keeps_market_open_while_claims_are_left_to_users() {
+        let p = Policy { claim_winners: false, ..policy() };
+        let m = BetMarket { resolved: true, winning_side: 1, ..market() };
+        assert_eq!(plan(&m, &[ticket(1)], 0, &p, true), vec![]);
+    }
+
+    #[test]
+    fn holds_claims_for_pending_prize_draw() {
+        let m = BetMarket { resolved: true, winning_side: 0, bettors_yes: 1, prize_seed_hash: [1; 32], ..market() };
+        let loser = ticket(1);
+        assert_eq!(plan(&m, &[ticket(0), loser.clone()], 0, &policy(), true), vec![Action::CloseTickets(vec![loser.user])]);
+        let drawn = BetMarket { prize_winner: Pubkey::new_unique(), ..m };
+        assert!(matches!(plan(&drawn, &[ticket(0)], 0, &policy(), true)[0], Action::Claim(_)));
+    }
+
+    #[test]
+    fn refunds_every_ticket_when_no_winner_policy_refunds() {
+        use punt_program::NoWinnerPolicy;
+        let m = BetMarket { resolved: true, winning_side: 0, pool_no: 10, no_winner_policy: NoWinnerPolicy::Refund, ..market() };
+        let actions = plan(&m, &[ticket(1), ticket(1)], 0, &policy(), true);
+        assert!(matches!(&actions[0], Action::Claim(users) if users.len() == 2));
+        let fees = BetMarket { no_winner_policy: NoWinnerPolicy::Fees, ..m };
+        assert!(matches!(&plan(&fees, &[ticket(1)], 0, &policy(), true)[0], Action::CloseTickets(_)));
+    }
+
+    #[test]
+    fn cranks_fees_and_close_without_authority_key() {
+        let m = BetMarket { resolved: true, winning_side: 0, fees_accrued: 5, ..market() };
+        let actions = plan(&m, &[ticket(0)], 0, &policy(), false);
+        assert!(matches!(&actions[0], Action::Claim(_)));
+        assert_eq!(actions[1..], [Action::WithdrawFees, Action::CloseMarket]);
+    }
+
+    #[test]
+    fn sweeps_and_closes_unclaimed_tickets_after_deadline() {
+        let m = BetMarket { resolved: true, winning_side: 0, bettors_yes: 1, claim_deadline: 100, ..market() };
+        let tickets = [ticket(0)];
+        assert!(matches!(plan(&m, &tickets, 99, &policy(), false)[0], Action::Claim(_)));
+        assert_eq!(
+            plan(&m, &tickets, 100, &policy(), false),
+            vec![Action::SweepUnclaimed, Action::CloseTickets(vec![tickets[0].user]), Action::CloseMarket]
+        );
+    }
+
+    #[test]
+    fn leaves_market_open_until_pool_is_rolled_over() {
+        let m = BetMarket { resolved: true, winning_side: 0, rollover_pending: 10, ..market() };
+        let tickets = [ticket(1)];
+        assert_eq!(plan(&m, &tickets, 0, &policy(), true), vec![Action::CloseTickets(vec![tickets[0].user])]);
+        let rolled = BetMarket { rollover_pending: 0, ..m };
+        assert_eq!(plan(&rolled, &[], 0, &policy(), true), vec![Action::CloseMarket]);
+    }
+
+    #[test]
+    fn expires_a_rollover_nobody_placed_and_closes() {
+        let m = BetMarket { resolved: true, winning_side: 0, rollover_pending: 10, resolved_at: 50, ..market() };
+        let deadline = m.rollover_deadline();
+        assert_eq!(deadline, 50 + ROLLOVER_TIMEOUT_SECS);
+        assert!(plan(&m, &[], deadline - 1, &policy(), false).is_empty());
+        assert_eq!(plan(&m, &[], deadline, &policy(), false), vec![Action::ExpireRollover, Action::CloseMarket]);
+        let p = Policy { close_markets: false, ..policy() };
+        assert!(plan(&m, &[], deadline, &p, false).is_empty());
+    }
+
+    #[test]
+    fn batches_tickets_of_closed_markets_only() {
+        let gone = Pubkey::new_unique();
+        let orphan = |_| BetTicket { market: gone, ..ticket(1) };
+        let tickets: Vec<BetTicket> = (0..3).map(orphan).chain([ticket(1)]).collect();
+        let batches = orphans(&tickets, &HashSet::from([gone]), &policy());
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(batches.concat().iter().all(|(m, _)| *m == gone));
+        let p = Policy { close_losing_tickets: false, ..policy() };
+        assert!(orphans(&tickets, &HashSet::from([gone]), &p).is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+//! Pure payout and fee arithmetic shared by punt-program's handlers and its off-chain clients.
+//!
+//! Everything here is `core`-only integer math (no `std`, no Anchor types), so the program,
+//! keepers and CLIs depend on this one crate, and the frontend's `lib/payout.ts` mirror is checked
+//! against the same vectors (`tests/vectors.json`). Functions return `None` on overflow or an
+//! impossible input; the program maps that to `BetError::MathOverflow`.
+
+#![no_std]
+
+/// Basis-point denominator (100%).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Pro-rata gross payout for a winning stake: `stake * total_pool / winning_pool`, rounded down.
+pub fn gross_payout(stake: u64, total_pool: u64, winning_pool: u64) -> Option<u64> {
+    if winning_pool == 0 {
+        return None;
+    }
+    let gross = (stake as u128).checked_mul(total_pool as u128)? / winning_pool as u128;
+    u64::try_from(gross).ok()
+}
+
+/// Fee charged on the profit portion (`gross - stake`) of a payout, rounded down.
+pub fn fee_on_profit(gross: u64, stake: u64, total_fee_bps: u64) -> Option<u64> {
+    let profit = gross.checked_sub(stake)?;
+    let fee = (profit as u128).checked_mul(total_fee_bps as u128)? / BPS_DENOMINATOR as u128;
+    u64::try_from(fee).ok()
+}
+
+/// Gross payout under the exact rounding policy: every winning ticket is paid pro rata
+/// (rounded down) except the last one, which receives whatever is left of the pool after
+/// `claimed_total` has been paid out, so no lamport of the pool is stranded in escrow.
+pub fn settled_gross(
+    stake: u64,
+    total_pool: u64,
+    winning_pool: u64,
+    claimed_total: u64,
+    is_last: bool,
+) -> Option<u64> {
+    if is_last {
+        if winning_pool == 0 {
+            return None;
+        }
+        total_pool.checked_sub(claimed_total)
+    } else {
+        gross_payout(stake, total_pool, winning_pool)
+    }
+}
+
+/// Splits a gross payout into `(payout, fee)`, where the fee is charged on profit only.
+pub fn net_payout(gross: u64, stake: u64, fee_bps: u16, host_fee_bps: u16) -> Option<(u64, u64)> {
+    let fee = fee_on_profit(gross, stake, fee_bps as u64 + host_fee_bps as u64)?;
+    Some((gross.checked_sub(fee)?, fee))
+}
+
+/// Splits an accrued fee amount into `(authority_share, host_share)` by bps weight.
+/// The host takes the rounding remainder; with no configured fees everything goes to the authority.
+pub fn split_fees(amount: u64, fee_bps: u16, host_fee_bps: u16) -> (u64, u64) {
+    let total_bps = fee_bps as u128 + host_fee_bps as u128;
+    let authority_share = if total_bps > 0 {
+        (amount as u128 * fee_bps as u128 / total_bps) as u64
+    } else {
+        amount
+    };
+    (authority_share, amount - authority_share)
+}
+
+/// Splits `amount` by bps `weights` (summing to `BPS_DENOMINATOR`), rounding each share down;
+/// the entry at `remainder_index` also takes the rounding remainder, so the shares sum to `amount`.
+pub fn split_by_weights<const N: usize>(amount: u64, weights: &[u16; N], remainder_index: usize) -> Option<[u64; N]> {
+    let mut shares = [0u64; N];
+    let mut paid = 0u64;
+    for (share, weight) in shares.iter_mut().zip(weights) {
+        *share = (amount as u128 * *weight as u128 / BPS_DENOMINATOR as u128) as u64;
+        paid = paid.checked_add(*share)?;
+    }
+    let remainder = amount.checked_sub(paid)?;
+    let last = shares.get_mut(remainder_index)?;
+    *last = last.checked_add(remainder)?;
+    Some(shares)
+}
+
+/// Referrer's cut of a ticket's fee at `referral_bps` of that fee, rounded down; the rest stays
+/// with the authority / host split.
+pub fn referral_share(fee: u64, referral_bps: u16) -> Option<u64> {
+    if referral_bps as u64 > BPS_DENOMINATOR {
+        return None;
+    }
+    Some((fee as u128 * referral_bps as u128 / BPS_DENOMINATOR as u128) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_claimer_takes_remainder() {
+        // Three equal winners split 10 lamports: 3 + 3 + 4.
+        let first = settled_gross(1, 10, 3, 0, false).unwrap();
+        let second = settled_gross(1, 10, 3, first, false).unwrap();
+        let last = settled_gross(1, 10, 3, first + second, true).unwrap();
+        assert_eq!((first, second, last), (3, 3, 4));
+    }
+
+    #[test]
+    fn rejects_empty_winning_pool() {
+        assert_eq!(gross_payout(1, 10, 0), None);
+    }
+
+    #[test]
+    fn split_is_exact() {
+        assert_eq!(split_fees(7, 20, 670), (0, 7));
+        assert_eq!(split_fees(690, 20, 670), (20, 670));
+        assert_eq!(split_fees(5, 0, 0), (5, 0));
+    }
+
+    #[test]
+    fn weighted_split_is_exact() {
+        // 50% / 30% / 20% of 101 lamports: 50 + 30 + 20, remainder 1 to the designated entry.
+        assert_eq!(split_by_weights(101, &[5_000, 3_000, 2_000], 2), Some([50, 30, 21]));
+        assert_eq!(split_by_weights(7, &[3_333, 3_333, 3_334, 0], 0), Some([3, 2, 2, 0]));
+        assert_eq!(split_by_weights(10, &[10_000], 1), None);
+        assert_eq!(split_by_weights(10, &[6_000, 6_000], 0), None);
+    }
+
+    #[test]
+    fn referral_share_comes_out_of_fee() {
+        assert_eq!(referral_share(45, 2_000), Some(9));
+        assert_eq!(referral_share(7, 1_000), Some(0));
+        assert_eq!(referral_share(45, 10_000), Some(45));
+        assert_eq!(referral_share(45, 10_001), None);
+    }
+}
@@ -0,0 +1,53 @@
+//! Runs the vectors shared with the frontend's `lib/payout.ts` mirror against this crate.
+
+use punt_math::{net_payout, referral_share, settled_gross};
+use serde_json::Value;
+
+fn vectors(name: &str) -> Vec<Value> {
+    let all: Value = serde_json::from_str(include_str!("vectors.json")).unwrap();
+    all[name].as_array().unwrap().clone()
+}
+
+fn amount(v: &Value) -> u64 {
+    v.as_str().unwrap().parse().unwrap()
+}
+
+fn optional(v: &Value) -> Option<u64> {
+    (!v.is_null()).then(|| amount(v))
+}
+
+#[test]
+fn settled_gross_vectors() {
+    for v in vectors("settled_gross") {
+        let got = settled_gross(
+            amount(&v["stake"]),
+            amount(&v["total_pool"]),
+            amount(&v["winning_pool"]),
+            amount(&v["claimed_total"]),
+            v["is_last"].as_bool().unwrap(),
+        );
+        assert_eq!(got, optional(&v["expected"]), "{}", v["note"]);
+    }
+}
+
+#[test]
+fn net_payout_vectors() {
+    for v in vectors("net_payout") {
+        let got = net_payout(
+            amount(&v["gross"]),
+            amount(&v["stake"]),
+            v["fee_bps"].as_u64().unwrap() as u16,
+            v["host_fee_bps"].as_u64().unwrap() as u16,
+        );
+        let expected = v["expected"].as_array().map(|e| (amount(&e[0]), amount(&e[1])));
+        assert_eq!(got, expected, "{}", v["note"]);
+    }
+}
+
+#[test]
+fn referral_share_vectors() {
+    for v in vectors("referral_share") {
+        let got = referral_share(amount(&v["fee"]), v["referral_bps"].as_u64().unwrap() as u16);
+        assert_eq!(got, optional(&v["expected"]));
+    }
+}
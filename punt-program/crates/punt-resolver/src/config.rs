@@ -0,0 +1,45 @@
+//! `resolver.toml` settings (see `resolver.example.toml`).
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub rpc_url: String,
+    pub resolver_keypair: PathBuf,
+    pub frames_dir: PathBuf,
+    #[serde(default = "default_poll_secs")]
+    pub poll_secs: u64,
+    #[serde(default)]
+    pub authorities: Vec<String>,
+    pub template: TemplateConfig,
+    pub rule: RuleConfig,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateConfig {
+    pub cards_csv: PathBuf,
+    #[serde(default = "default_max_distance")]
+    pub max_distance: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RuleConfig {
+    pub hit_rarities: Vec<String>,
+}
+
+fn default_poll_secs() -> u64 {
+    5
+}
+
+fn default_max_distance() -> u32 {
+    10
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))
+    }
+}
@@ -0,0 +1,124 @@
+//! Detector interface and the rule that turns detections into a market verdict.
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// One sampled video frame (encoded image bytes as captured).
+pub struct Frame {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A card recognised in a frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Detection {
+    pub card: String,
+    pub rarity: String,
+    pub score_bps: u16, // match quality, 10_000 = exact
+}
+
+/// Recognises cards in frames. Implementations must be deterministic for a given input so the
+/// recorded evidence hash can be reproduced during disputes.
+pub trait Detector {
+    fn name(&self) -> &'static str;
+    fn detect(&self, frame: &Frame) -> Result<Vec<Detection>>;
+}
+
+/// Result to submit on chain via `resolve_market`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Verdict {
+    pub winning_side: u8,
+    pub confidence_bps: u16,
+    pub evidence_hash: [u8; 32],
+    pub summary: String,
+}
+
+/// Runs `detector` over `frames` and applies the hit rule: YES (0) if any detected card has a hit
+/// rarity, NO (1) otherwise. Returns `None` when nothing was recognised (manual resolution needed).
+pub fn evaluate(detector: &dyn Detector, frames: &[Frame], hit_rarities: &[String]) -> Result<Option<Verdict>> {
+    // Best score per card across all frames.
+    let mut best: BTreeMap<String, Detection> = BTreeMap::new();
+    for frame in frames {
+        for d in detector.detect(frame)? {
+            match best.get(&d.card) {
+                Some(prev) if prev.score_bps >= d.score_bps => {}
+                _ => {
+                    best.insert(d.card.clone(), d);
+                }
+            }
+        }
+    }
+    if best.is_empty() {
+        return Ok(None);
+    }
+
+    let hits: Vec<&Detection> = best.values().filter(|d| hit_rarities.contains(&d.rarity)).collect();
+    let (winning_side, confidence_bps) = if hits.is_empty() {
+        // "No hit" is only as certain as the weakest card identification.
+        (1, best.values().map(|d| d.score_bps).min().unwrap_or(0))
+    } else {
+        (0, hits.iter().map(|d| d.score_bps).max().unwrap_or(0))
+    };
+    let summary = best
+        .values()
+        .map(|d| format!("{} ({}) {}bps", d.card, d.rarity, d.score_bps))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let summary = format!("{}: {}", detector.name(), summary);
+    Ok(Some(Verdict { winning_side, confidence_bps, evidence_hash: evidence_hash(frames, &summary), summary }))
+}
+
+/// SHA-256 over every frame (name + bytes, in order) followed by the detector summary.
+pub fn evidence_hash(frames: &[Frame], summary: &str) -> [u8; 32] {
+    let mut h = Sha256::new();
+    for f in frames {
+        h.update(f.name.as_bytes());
+        h.update((f.bytes.len() as u64).to_le_bytes());
+        h.update(&f.bytes);
+    }
+    h.update(summary.as_bytes());
+    h.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(Vec<Detection>);
+    impl Detector for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+        fn detect(&self, _frame: &Frame) -> Result<Vec<Detection>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn det(card: &str, rarity: &str, score_bps: u16) -> Detection {
+        Detection { card: card.into(), rarity: rarity.into(), score_bps }
+    }
+
+    fn frame() -> Frame {
+        Frame { name: "f0.png".into(), bytes: vec![1, 2, 3] }
+    }
+
+    #[test]
+    fn hit_resolves_yes() {
+        let d = Fixed(vec![det("Pikachu", "Common", 9_000), det("Charizard ex", "Double Rare", 9_500)]);
+        let v = evaluate(&d, &[frame()], &["Double Rare".into()]).unwrap().unwrap();
+        assert_eq!((v.winning_side, v.confidence_bps), (0, 9_500));
+    }
+
+    #[test]
+    fn no_hit_resolves_no_with_weakest_confidence() {
+        let d = Fixed(vec![det("Pikachu", "Common", 9_000), det("Eevee", "Common", 8_000)]);
+        let v = evaluate(&d, &[frame()], &["Double Rare".into()]).unwrap().unwrap();
+        assert_eq!((v.winning_side, v.confidence_bps), (1, 8_000));
+    }
+
+    #[test]
+    fn nothing_detected_needs_manual_resolution() {
+        assert_eq!(evaluate(&Fixed(vec![]), &[frame()], &[]).unwrap(), None);
+    }
+}
@@ -0,0 +1,198 @@
+//! punt-resolver: watches for frozen, unresolved markets, runs the configured card detector over
+//! sampled frames and signs `resolve_market` with the delegated resolver key.
+//!
+//! Frames for a market are read from `<frames_dir>/<market pubkey>/` (PNG/JPEG, sorted by name);
+//! the capture side (LiveKit sampler) is expected to drop them there after `freeze_market` and to
+//! create an empty `CAPTURE_DONE` file there once the last frame is written. Until then the market
+//! is not evaluated, so a verdict is never drawn from a partial capture.
+
+mod config;
+mod detector;
+mod template;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use clap::Parser;
+use config::Config;
+use detector::{Detector, Frame};
+use punt_client::{accounts, ix, keypair, RpcClient};
+use punt_program::{BetMarket, MarketKind, ResolutionSource};
+use solana_signer::Signer;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Marker file the capture side writes into a market's frame directory once it is complete.
+const CAPTURE_DONE: &str = "CAPTURE_DONE";
+
+#[derive(Parser)]
+#[command(about = "Resolve frozen punt markets from stream frames")]
+struct Args {
+    /// Path to resolver.toml
+    #[arg(long, default_value = "resolver.toml")]
+    config: PathBuf,
+    /// Scan once and exit instead of polling.
+    #[arg(long)]
+    once: bool,
+    /// Evaluate and log verdicts without sending transactions.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let cfg = Config::load(&args.config)?;
+    let rpc = RpcClient::new(&cfg.rpc_url);
+    let signer = keypair::read_keypair(&cfg.resolver_keypair)?;
+    let detector = template::TemplateDetector::from_csv(&cfg.template.cards_csv, cfg.template.max_distance)?;
+    let authorities = cfg
+        .authorities
+        .iter()
+        .map(|a| Pubkey::from_str(a).with_context(|| format!("invalid authority {a}")))
+        .collect::<Result<HashSet<_>>>()?;
+    println!("[resolver] {} as {} on {}", detector.name(), signer.pubkey(), rpc.url());
+
+    // Markets already handled (resolved or submitted) this run, so failures are not retried in a tight loop.
+    let mut done: HashSet<Pubkey> = HashSet::new();
+    loop {
+        if let Err(e) = scan(&rpc, &cfg, &detector, &signer, &authorities, &mut done, args.dry_run) {
+            eprintln!("[resolver] scan failed: {e:#}");
+        }
+        if args.once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(cfg.poll_secs));
+    }
+}
+
+fn scan(
+    rpc: &RpcClient,
+    cfg: &Config,
+    detector: &dyn Detector,
+    signer: &solana_keypair::Keypair,
+    authorities: &HashSet<Pubkey>,
+    done: &mut HashSet<Pubkey>,
+    dry_run: bool,
+) -> Result<()> {
+    for (key, market) in accounts::fetch_markets(rpc)? {
+        if done.contains(&key) || !awaiting_resolution(&market) {
+            continue;
+        }
+        if !authorities.is_empty() && !authorities.contains(&market.authority) {
+            continue;
+        }
+        // One market's unreadable frames or failed detection must not hold up the others; it is
+        // retried on the next scan.
+        match resolve(rpc, cfg, detector, signer, &key, &market, dry_run) {
+            Ok(true) => {
+                done.insert(key);
+            }
+            Ok(false) => {}
+            Err(e) => eprintln!("[resolver] {key}: {e:#}"),
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates one market's capture and submits the verdict. Returns whether the market is handled
+/// (false while its capture is still in progress).
+fn resolve(
+    rpc: &RpcClient,
+    cfg: &Config,
+    detector: &dyn Detector,
+    signer: &solana_keypair::Keypair,
+    key: &Pubkey,
+    market: &BetMarket,
+    dry_run: bool,
+) -> Result<bool> {
+    let Some(frames) = load_frames(&cfg.frames_dir.join(key.to_string()))? else {
+        return Ok(false); // capture not finished yet
+    };
+    let title = accounts::fixed_str(&market.title);
+    let Some(verdict) = detector::evaluate(detector, &frames, &cfg.rule.hit_rarities)? else {
+        println!("[resolver] {key} \"{title}\": no cards detected in {} frames; manual resolution required", frames.len());
+        return Ok(true);
+    };
+    println!(
+        "[resolver] {key} \"{title}\": side {} ({}bps) from {}",
+        verdict.winning_side, verdict.confidence_bps, verdict.summary
+    );
+    if dry_run {
+        return Ok(true);
+    }
+    let ix = ix::resolve_market(
+        &signer.pubkey(),
+        key,
+        verdict.winning_side,
+        verdict.evidence_hash,
+        verdict.confidence_bps,
+        ResolutionSource::AiOcr,
+    );
+    match rpc.send_and_confirm(&[ix], signer, &[]) {
+        Ok(sig) => println!("[resolver] {key} resolved: {sig}"),
+        Err(e) => eprintln!("[resolver] {key} resolve failed: {e:#}"),
+    }
+    Ok(true)
+}
+
+/// Frozen standard markets below any quorum threshold are the ones a single resolver key may settle.
+fn awaiting_resolution(m: &BetMarket) -> bool {
+    let pool = m.pool_yes.saturating_add(m.pool_no);
+    m.frozen && !m.resolved && m.kind == MarketKind::Standard && (m.quorum_threshold == 0 || pool < m.quorum_threshold)
+}
+
+/// The frames of a complete capture (`CAPTURE_DONE` present), or `None` while it is in progress.
+fn load_frames(dir: &Path) -> Result<Option<Vec<Frame>>> {
+    if !dir.join(CAPTURE_DONE).is_file() {
+        return Ok(None);
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("listing {}", dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            matches!(
+                p.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref(),
+                Some("png" | "jpg" | "jpeg")
+            )
+        })
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|p| {
+            let bytes = std::fs::read(&p).with_context(|| format!("reading {}", p.display()))?;
+            let name = p.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            Ok(Frame { name, bytes })
+        })
+        .collect::<Result<_>>()
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("punt-resolver-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("0001.png"), b"frame").unwrap();
+        dir
+    }
+
+    #[test]
+    fn waits_for_capture_done_marker() {
+        let dir = capture_dir("partial");
+        assert!(load_frames(&dir).unwrap().is_none());
+        std::fs::write(dir.join(CAPTURE_DONE), b"").unwrap();
+        let frames = load_frames(&dir).unwrap().unwrap();
+        assert_eq!(frames.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), ["0001.png"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_directory_is_not_ready() {
+        assert!(load_frames(Path::new("/nonexistent/punt-resolver")).unwrap().is_none());
+    }
+}
@@ -0,0 +1,117 @@
+//! Offline card detector: perceptual (difference) hashes of frames matched against card
+//! template images listed in the card CSV. Frames are expected to be card-region crops.
+
+use crate::detector::{Detection, Detector, Frame};
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct CardRow {
+    name: String,
+    rarity: String,
+    image: String,
+}
+
+struct Template {
+    card: String,
+    rarity: String,
+    hash: u64,
+}
+
+pub struct TemplateDetector {
+    templates: Vec<Template>,
+    max_distance: u32,
+}
+
+impl TemplateDetector {
+    /// Loads `name,rarity,image` rows; image paths are relative to the CSV's directory.
+    pub fn from_csv(path: &Path, max_distance: u32) -> Result<Self> {
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut reader = csv::Reader::from_path(path).with_context(|| format!("opening {}", path.display()))?;
+        let mut templates = Vec::new();
+        for row in reader.deserialize() {
+            let row: CardRow = row.with_context(|| format!("parsing {}", path.display()))?;
+            let image_path = base.join(&row.image);
+            let img = image::open(&image_path).with_context(|| format!("loading template {}", image_path.display()))?;
+            templates.push(Template { card: row.name, rarity: row.rarity, hash: dhash(&img) });
+        }
+        Ok(Self { templates, max_distance })
+    }
+
+    fn best_match(&self, hash: u64) -> Option<(&Template, u32)> {
+        self.templates
+            .iter()
+            .map(|t| (t, (t.hash ^ hash).count_ones()))
+            .filter(|(_, dist)| *dist <= self.max_distance)
+            .min_by_key(|(_, dist)| *dist)
+    }
+}
+
+impl Detector for TemplateDetector {
+    fn name(&self) -> &'static str {
+        "template-dhash"
+    }
+
+    fn detect(&self, frame: &Frame) -> Result<Vec<Detection>> {
+        let img = image::load_from_memory(&frame.bytes).with_context(|| format!("decoding frame {}", frame.name))?;
+        Ok(self
+            .best_match(dhash(&img))
+            .map(|(t, dist)| Detection {
+                card: t.card.clone(),
+                rarity: t.rarity.clone(),
+                score_bps: ((64 - dist) * 10_000 / 64) as u16,
+            })
+            .into_iter()
+            .collect())
+    }
+}
+
+/// 64-bit difference hash: grayscale 9x8, one bit per horizontally adjacent pixel pair.
+pub fn dhash(img: &DynamicImage) -> u64 {
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let bit = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | bit as u64;
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn gradient(flip: bool) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(64, 64, |x, _| {
+            let v = (x * 4) as u8;
+            Luma([if flip { 255 - v } else { v }])
+        }))
+    }
+
+    #[test]
+    fn identical_images_match_exactly() {
+        assert_eq!(dhash(&gradient(false)), dhash(&gradient(false)));
+    }
+
+    #[test]
+    fn detects_closest_template() {
+        let detector = TemplateDetector {
+            templates: vec![
+                Template { card: "Dark".into(), rarity: "Common".into(), hash: dhash(&gradient(false)) },
+                Template { card: "Light".into(), rarity: "Ultra Rare".into(), hash: dhash(&gradient(true)) },
+            ],
+            max_distance: 4,
+        };
+        let mut png = Vec::new();
+        gradient(true).write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+        let found = detector.detect(&Frame { name: "f.png".into(), bytes: png }).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].card.as_str(), found[0].score_bps), ("Light", 10_000));
+    }
+}
@@ -0,0 +1,46 @@
+// anchor 0.31 `#[program]` (idl-build) expands to the deprecated AccountInfo::realloc.
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use punt_program::feed::{self, FeedValue, FEED_LEN};
+
+declare_id!("CmYKawhke7KMLox1hiDd9jcHu3C2VankftEFUoY75AFW");
+
+// -------------------------------------------------------------------------------------------------
+// Program
+// -------------------------------------------------------------------------------------------------
+/// Test-only feed publisher for oracle markets. The client creates a FEED_LEN-byte account owned
+/// by this program, then anyone may overwrite its observation.
+#[program]
+pub mod mock_feed {
+    use super::*;
+
+    /// Publish `value` observed at `updated_at` (unix seconds) into the feed account.
+    pub fn set_value(ctx: Context<SetValue>, value: i64, updated_at: i64) -> Result<()> {
+        let feed_info = ctx.accounts.feed.to_account_info();
+        require_keys_eq!(*feed_info.owner, crate::ID, MockFeedError::WrongOwner);
+        let mut data = feed_info.try_borrow_mut_data()?;
+        require!(data.len() >= FEED_LEN, MockFeedError::AccountTooSmall);
+        data[..FEED_LEN].copy_from_slice(&feed::encode(&FeedValue { value, updated_at }));
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// Accounts
+// -------------------------------------------------------------------------------------------------
+#[derive(Accounts)]
+pub struct SetValue<'info> {
+    /// CHECK: Raw feed layout (no Anchor discriminator); owner checked in handler.
+    #[account(mut)]
+    pub feed: UncheckedAccount<'info>,
+}
+
+// -------------------------------------------------------------------------------------------------
+// Errors
+// -------------------------------------------------------------------------------------------------
+#[error_code]
+pub enum MockFeedError {
+    #[msg("Feed account not owned by mock-feed")] WrongOwner,
+    #[msg("Feed account too small")] AccountTooSmall,
+}
@@ -0,0 +1,57 @@
+//! Value-feed account layout read by `resolve_from_feed`.
+//!
+//! Deliberately minimal so any program (a real price relay, or `mock-feed` on localnet) can
+//! publish it. All integers are little-endian, starting at offset 0 of the account data:
+//!
+//! | bytes  | field        | notes                                  |
+//! |--------|--------------|----------------------------------------|
+//! | 0..8   | magic        | `b"PUNTFEED"`                          |
+//! | 8..16  | value        | `i64`, same units as the market threshold |
+//! | 16..24 | `updated_at` | `i64` unix seconds of the observation   |
+
+pub const FEED_MAGIC: [u8; 8] = *b"PUNTFEED";
+pub const FEED_LEN: usize = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeedValue {
+    pub value: i64,
+    pub updated_at: i64,
+}
+
+/// Parses a feed account's data; `None` if it is too short or the magic does not match.
+pub fn parse(data: &[u8]) -> Option<FeedValue> {
+    if data.len() < FEED_LEN || data[..8] != FEED_MAGIC {
+        return None;
+    }
+    let value = i64::from_le_bytes(data[8..16].try_into().ok()?);
+    let updated_at = i64::from_le_bytes(data[16..24].try_into().ok()?);
+    Some(FeedValue { value, updated_at })
+}
+
+/// Serializes a feed value (used by the mock feed program and off-chain tooling).
+pub fn encode(value: &FeedValue) -> [u8; FEED_LEN] {
+    let mut out = [0u8; FEED_LEN];
+    out[..8].copy_from_slice(&FEED_MAGIC);
+    out[8..16].copy_from_slice(&value.value.to_le_bytes());
+    out[16..24].copy_from_slice(&value.updated_at.to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let v = FeedValue { value: -42, updated_at: 1_700_000_000 };
+        assert_eq!(parse(&encode(&v)), Some(v));
+    }
+
+    #[test]
+    fn rejects_bad_magic_or_length() {
+        let mut data = encode(&FeedValue { value: 1, updated_at: 2 });
+        assert_eq!(parse(&data[..FEED_LEN - 1]), None);
+        data[0] = b'X';
+        assert_eq!(parse(&data), None);
+    }
+}
@@ -1,7 +1,16 @@
+// anchor 0.31 `#[program]` generates its IDL instructions in a crate-root `__private` module
+// whose resize handler calls the deprecated AccountInfo::realloc; an allow on the program
+// module does not reach it.
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
 use anchor_lang::system_program;
 use std::str::FromStr;
 
+pub mod feed;
+pub use punt_math as math;
+
 declare_id!("3ke7tRTEFF8qr9pJLmufeb9xiPdatFq5K3GSqUQhbbw1");
 
 // -------------------------------------------------------------------------------------------------
@@ -11,66 +20,162 @@ declare_id!("3ke7tRTEFF8qr9pJLmufeb9xiPdatFq5K3GSqUQhbbw1");
 pub mod punt_program {
     use super::*;
 
-    /// Initialize authority meta (one-time per authority) holding cycle counter.
+    /// Initialize authority meta (one-time per authority) holding the market id high-water mark.
     pub fn init_authority_meta(ctx: Context<InitAuthorityMeta>) -> Result<()> {
         let meta = &mut ctx.accounts.authority_meta;
         meta.authority = ctx.accounts.authority.key();
         meta.next_cycle = 0;
         meta.bump = ctx.bumps.authority_meta;
+        meta.markets_opened = 0;
+        Ok(())
+    }
+
+    /// Rewrite a pre-u64 `AuthorityMeta` (u16 `next_cycle`) in the current layout, topping up
+    /// rent from the authority. Existing market ids carry over unchanged.
+    pub fn migrate_authority_meta(ctx: Context<MigrateAuthorityMeta>) -> Result<()> {
+        let info = ctx.accounts.authority_meta.to_account_info();
+        require_keys_eq!(*info.owner, crate::ID, BetError::Unauthorized);
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() == 8 + LegacyAuthorityMeta::SIZE && &data[..8] == AuthorityMeta::DISCRIMINATOR,
+                BetError::AlreadyMigrated
+            );
+            LegacyAuthorityMeta::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(legacy.authority, ctx.accounts.authority.key(), BetError::Unauthorized);
+
+        let new_len = 8 + AuthorityMeta::SIZE;
+        let rent_due = Rent::get()?.minimum_balance(new_len).saturating_sub(info.lamports());
+        if rent_due > 0 {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: info.clone(),
+                },
+            );
+            system_program::transfer(cpi_ctx, rent_due)?;
+        }
+        info.resize(new_len)?;
+        let meta = AuthorityMeta {
+            authority: legacy.authority,
+            next_cycle: legacy.next_cycle as u64,
+            bump: ctx.bumps.authority_meta,
+            // Legacy ids were handed out one per market, so serials continue past them.
+            markets_opened: legacy.next_cycle as u64,
+        };
+        meta.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
         Ok(())
     }
 
-    /// Initialize a single market for the authority (1 market per authority in this simple PoC)
+    /// Initialize a market under the client-chosen id `cycle`: any id the authority has no open
+    /// market under, in any order, so concurrent creates never contend on `AuthorityMeta`. Any number
+    /// of an authority's markets may be open at once. Reusing the id of a closed market is safe:
+    /// tickets left behind under that address carry the old market's `serial` and are refused.
     /// Added naming fields: title, label_yes, label_no for richer streamer UX.
     pub fn initialize_market(
         ctx: Context<InitializeMarket>,
+        cycle: u64,
         title: String,
         label_yes: String,
         label_no: String,
-        fee_bps: Option<u16>
+        fee_bps: Option<u16>,
+        options: Option<MarketOptions>,
     ) -> Result<()> {
         let meta = &mut ctx.accounts.authority_meta;
-        // Use current cycle; then increment for next time.
-        let cycle = meta.next_cycle;
         let market = &mut ctx.accounts.market;
-        market.authority = ctx.accounts.authority.key();
-        market.cycle = cycle;
-        market.pool_yes = 0;
-        market.pool_no = 0;
-        market.resolved = false;
-        market.frozen = false;
-    market.fee_bps = fee_bps.unwrap_or(AUTHORITY_FEE_BPS_DEFAULT);
-    market.host_fee_bps = HOST_FEE_BPS_DEFAULT;
-    require!(market.fee_bps <= 10_000, BetError::InvalidFee);
-    require!(market.host_fee_bps <= 10_000, BetError::InvalidFee);
-    require!(market.fee_bps as u32 + market.host_fee_bps as u32 <= 10_000, BetError::InvalidFee);
-        market.bump = ctx.bumps.market;
-        market.winning_side = 255; // sentinel for not set
-        market.fees_accrued = 0;
-        // Write labels (truncate if needed; enforce length limit)
-        require!(title.as_bytes().len() <= TITLE_MAX_LEN, BetError::LabelTooLong);
-        require!(label_yes.as_bytes().len() <= LABEL_MAX_LEN, BetError::LabelTooLong);
-        require!(label_no.as_bytes().len() <= LABEL_MAX_LEN, BetError::LabelTooLong);
-        write_fixed(&mut market.title, title.as_bytes());
-        write_fixed(&mut market.label_yes, label_yes.as_bytes());
-        write_fixed(&mut market.label_no, label_no.as_bytes());
-        // Increment meta so next initialization gets a new cycle (unique market PDA)
-        meta.next_cycle = meta.next_cycle.checked_add(1).ok_or(BetError::MathOverflow)?;
+        init_market_state(
+            market,
+            ctx.accounts.authority.key(),
+            cycle,
+            ctx.bumps.market,
+            fee_bps.unwrap_or(AUTHORITY_FEE_BPS_DEFAULT),
+            options.unwrap_or_default(),
+        )?;
+        // Write labels (validated; enforce length limit)
+        write_market_text(market, &title, &label_yes, &label_no)?;
+        market.serial = take_cycle(meta, cycle)?;
+        Ok(())
+    }
+
+    /// Save a reusable market preset under the authority's meta. `id` is chosen by the authority.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_market_template(
+        ctx: Context<CreateMarketTemplate>,
+        id: u16,
+        title: String,
+        label_yes: String,
+        label_no: String,
+        fee_bps: u16,
+        limits: BetLimits,
+        duration_secs: u32,
+    ) -> Result<()> {
+        check_market_text(&title, &label_yes, &label_no)?;
+        check_fee(fee_bps)?;
+        check_limits(&limits)?;
+        let template = &mut ctx.accounts.template;
+        template.authority = ctx.accounts.authority.key();
+        template.id = id;
+        write_fixed(&mut template.title, &title);
+        write_fixed(&mut template.label_yes, &label_yes);
+        write_fixed(&mut template.label_no, &label_no);
+        template.fee_bps = fee_bps;
+        template.limits = limits;
+        template.duration_secs = duration_secs;
+        template.bump = ctx.bumps.template;
+        Ok(())
+    }
+
+    /// Delete a template, returning its rent to the authority.
+    pub fn close_market_template(_ctx: Context<CloseMarketTemplate>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Create market `cycle` from a template. Betting closes `duration_secs` from now
+    /// (never, if zero). Takes the id exactly like `initialize_market`.
+    pub fn initialize_market_from_template(ctx: Context<InitializeMarketFromTemplate>, cycle: u64) -> Result<()> {
+        let template = &ctx.accounts.template;
+        let close_ts = match template.duration_secs {
+            0 => 0,
+            secs => Clock::get()?.unix_timestamp.checked_add(secs as i64).ok_or(BetError::MathOverflow)?,
+        };
+        let options = MarketOptions { limits: template.limits, close_ts, ..Default::default() };
+        let meta = &mut ctx.accounts.authority_meta;
+        let market = &mut ctx.accounts.market;
+        init_market_state(market, ctx.accounts.authority.key(), cycle, ctx.bumps.market, template.fee_bps, options)?;
+        // Text was validated when the template was created.
+        market.title = template.title;
+        market.label_yes = template.label_yes;
+        market.label_no = template.label_no;
+        market.serial = take_cycle(meta, cycle)?;
         Ok(())
     }
 
-    /// Create a ticket (locks in side). One ticket per (user, market).
-    pub fn create_ticket(ctx: Context<CreateTicket>, side: u8) -> Result<()> {
+    /// Open a ticket on `side` (one ticket per user and market). An optional `referrer` (never the bettor or the market authority)
+    /// earns the market's `referral_bps` share of this ticket's fees; their vault must exist.
+    pub fn create_ticket(ctx: Context<CreateTicket>, side: u8, referrer: Option<Pubkey>) -> Result<()> {
         require!(side <= 1, BetError::InvalidSide);
         let market = &ctx.accounts.market;
         require!(!market.resolved, BetError::MarketAlreadyResolved);
         require!(ctx.accounts.user.key() != market.authority, BetError::AuthorityCannotBet);
+        if let Some(referrer) = referrer {
+            require!(
+                referrer != ctx.accounts.user.key() && referrer != market.authority && referrer != Pubkey::default(),
+                BetError::InvalidReferrer
+            );
+            let vault = ctx.accounts.referrer_vault.as_ref().ok_or(BetError::ReferrerVaultMissing)?;
+            require!(vault.referrer == referrer, BetError::ReferrerVaultMissing);
+        }
         let ticket = &mut ctx.accounts.ticket;
+        ticket.referrer = referrer.unwrap_or_default();
         ticket.user = ctx.accounts.user.key();
         ticket.market = market.key();
         ticket.side = side;
         ticket.amount = 0;
         ticket.claimed = false;
+        ticket.entry_index = 0;
+        ticket.market_serial = market.serial;
         ticket.bump = ctx.bumps.ticket;
         Ok(())
     }
@@ -81,9 +186,32 @@ pub mod punt_program {
         let market = &mut ctx.accounts.market;
         require!(!market.resolved, BetError::MarketAlreadyResolved);
         require!(!market.frozen, BetError::MarketFrozen);
+        let now = Clock::get()?.unix_timestamp;
+        if market.kind == MarketKind::Oracle {
+            require!(now < market.resolve_ts, BetError::BettingClosed);
+        }
+        require!(market.close_ts == 0 || now < market.close_ts, BetError::BettingClosed);
         let ticket = &mut ctx.accounts.ticket;
         require!(!ticket.claimed, BetError::AlreadyClaimed);
         require!(ctx.accounts.user.key() != market.authority, BetError::AuthorityCannotBet);
+        // Fixed-stake event markets: one entry of exactly the set stake per ticket.
+        if market.fixed_stake > 0 {
+            require!(amount == market.fixed_stake, BetError::FixedStakeMismatch);
+            require!(ticket.amount == 0, BetError::AlreadyStaked);
+        }
+        // Per-market limits (0 = unlimited)
+        require!(amount >= market.min_bet, BetError::BetBelowMinimum);
+        let ticket_total = ticket.amount.checked_add(amount).ok_or(BetError::MathOverflow)?;
+        require!(
+            market.max_bet_per_ticket == 0 || ticket_total <= market.max_bet_per_ticket,
+            BetError::BetAboveMaximum
+        );
+        let pool_total = market
+            .pool_yes
+            .checked_add(market.pool_no)
+            .and_then(|p| p.checked_add(amount))
+            .ok_or(BetError::MathOverflow)?;
+        require!(market.max_pool == 0 || pool_total <= market.max_pool, BetError::PoolCapExceeded);
 
         // Transfer lamports into market escrow
         let cpi_ctx = CpiContext::new(
@@ -95,19 +223,46 @@ pub mod punt_program {
         );
         system_program::transfer(cpi_ctx, amount)?;
 
+        // A ticket counts as a bettor on its side from its first staked lamport, which also fixes its entry order.
+        let first_stake = ticket.amount == 0;
         ticket.amount = ticket.amount.checked_add(amount).ok_or(BetError::MathOverflow)?;
         match ticket.side {
-            0 => market.pool_yes = market.pool_yes.checked_add(amount).ok_or(BetError::MathOverflow)?,
-            1 => market.pool_no = market.pool_no.checked_add(amount).ok_or(BetError::MathOverflow)?,
+            0 => {
+                market.pool_yes = market.pool_yes.checked_add(amount).ok_or(BetError::MathOverflow)?;
+                if first_stake {
+                    ticket.entry_index = market.bettors_yes;
+                    market.bettors_yes = market.bettors_yes.checked_add(1).ok_or(BetError::MathOverflow)?;
+                }
+            }
+            1 => {
+                market.pool_no = market.pool_no.checked_add(amount).ok_or(BetError::MathOverflow)?;
+                if first_stake {
+                    ticket.entry_index = market.bettors_no;
+                    market.bettors_no = market.bettors_no.checked_add(1).ok_or(BetError::MathOverflow)?;
+                }
+            }
             _ => return err!(BetError::InvalidSide),
         }
         Ok(())
     }
 
 
-    /// Resolve the market selecting a winning side (0=yes,1=no). Only authority.
-    pub fn resolve_market(ctx: Context<ResolveMarket>, winning_side: u8) -> Result<()> {
-        require!(winning_side <= 1, BetError::InvalidWinningSide);
+    /// Resolve the market selecting a winning side (0=yes,1=no), or SIDE_VOID for a no contest
+    /// (stream crashed, duplicate pack) in which every ticket reclaims its stake fee-free.
+    /// Records what the resolver based the result on (evidence hash, confidence, source) for audits and disputes.
+    pub fn resolve_market(
+        ctx: Context<ResolveMarket>,
+        winning_side: u8,
+        evidence_hash: [u8; 32],
+        confidence_bps: u16,
+        source: ResolutionSource,
+    ) -> Result<()> {
+        require!(winning_side <= 1 || winning_side == SIDE_VOID, BetError::InvalidWinningSide);
+        require!(confidence_bps <= 10_000, BetError::InvalidConfidence);
+        require!(
+            matches!(source, ResolutionSource::Manual | ResolutionSource::AiOcr),
+            BetError::InvalidResolutionSource
+        );
         let market = &mut ctx.accounts.market;
         let resolver = &ctx.accounts.resolver;
 
@@ -122,27 +277,266 @@ pub mod punt_program {
 
         require!(!market.resolved, BetError::MarketAlreadyResolved);
         require!(market.frozen, BetError::MarketNotFrozen);
+        require!(market.kind == MarketKind::Standard, BetError::OracleMarket);
+        // High-value markets must go through the ResolutionVote quorum instead of a single key.
+        let total_pool = market.pool_yes.checked_add(market.pool_no).ok_or(BetError::MathOverflow)?;
+        require!(
+            market.quorum_threshold == 0 || total_pool < market.quorum_threshold,
+            BetError::QuorumRequired
+        );
         market.resolved = true;
-        market.winning_side = winning_side;
-        // If selected winning side has zero bets, convert entire opposing pool to fees immediately.
-        let (winning_pool, losing_pool) = match winning_side { 0 => (market.pool_yes, market.pool_no), 1 => (market.pool_no, market.pool_yes), _ => (0,0) };
-        if winning_pool == 0 && losing_pool > 0 {
-            // All losing_pool lamports become fees (since no winners). Accrue as total fees to be split on withdraw.
-            // They are currently still inside the market escrow account: just add to fees_accrued.
-            market.fees_accrued = market.fees_accrued.checked_add(losing_pool).ok_or(BetError::MathOverflow)?;
-            // Zero out the losing pool to reflect accounting invariant: pool sums = remaining claimable + already-fees.
-            if winning_side == 0 { market.pool_no = 0; } else { market.pool_yes = 0; }
+        market.resolved_at = Clock::get()?.unix_timestamp;
+        market.evidence_hash = evidence_hash;
+        market.confidence_bps = confidence_bps;
+        market.resolution_source = source;
+        apply_resolution(market, winning_side)?;
+        Ok(())
+    }
+
+    /// Open a K-of-N resolution vote for the market. Only authority; market must be frozen and unresolved.
+    /// K must be at least MIN_VOTE_QUORUM and a strict majority of N, and the authority cannot be a resolver.
+    pub fn init_resolution_vote(
+        ctx: Context<InitResolutionVote>,
+        resolvers: Vec<Pubkey>,
+        quorum: u8,
+        timeout_secs: u32,
+    ) -> Result<()> {
+        let market = &ctx.accounts.market;
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(market.frozen, BetError::MarketNotFrozen);
+        require!(market.kind == MarketKind::Standard, BetError::OracleMarket);
+        require!(!resolvers.is_empty() && resolvers.len() <= MAX_RESOLVERS, BetError::InvalidQuorum);
+        require!(quorum >= MIN_VOTE_QUORUM && quorum as usize <= resolvers.len(), BetError::InvalidQuorum);
+        require!(quorum as usize * 2 > resolvers.len(), BetError::InvalidQuorum);
+        require!(timeout_secs > 0, BetError::InvalidQuorum);
+        for (i, r) in resolvers.iter().enumerate() {
+            require!(*r != market.authority && !resolvers[..i].contains(r), BetError::InvalidQuorum);
         }
-        let no_winner = winning_pool == 0;
-        emit!(MarketResolvedEvent {
+        let vote = &mut ctx.accounts.vote;
+        vote.market = market.key();
+        vote.resolvers = [Pubkey::default(); MAX_RESOLVERS];
+        vote.resolvers[..resolvers.len()].copy_from_slice(&resolvers);
+        vote.resolver_count = resolvers.len() as u8;
+        vote.quorum = quorum;
+        vote.votes = [VOTE_NONE; MAX_RESOLVERS];
+        vote.opened_at = Clock::get()?.unix_timestamp;
+        vote.timeout_secs = timeout_secs;
+        vote.bump = ctx.bumps.vote;
+        Ok(())
+    }
+
+    /// Cast a resolver's vote. Resolves the market once a side reaches quorum, or voids it
+    /// (full refunds) once no side can reach quorum anymore.
+    pub fn submit_resolution_vote(ctx: Context<SubmitResolutionVote>, winning_side: u8) -> Result<()> {
+        require!(winning_side <= 1, BetError::InvalidWinningSide);
+        let market = &mut ctx.accounts.market;
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(market.frozen, BetError::MarketNotFrozen);
+        let vote = &mut ctx.accounts.vote;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < vote_deadline(vote)?, BetError::VoteExpired);
+        let idx = vote.resolvers[..vote.resolver_count as usize]
+            .iter()
+            .position(|r| *r == ctx.accounts.resolver.key())
+            .ok_or(BetError::Unauthorized)?;
+        require!(vote.votes[idx] == VOTE_NONE, BetError::AlreadyVoted);
+        vote.votes[idx] = winning_side;
+        emit!(ResolutionVoteEvent {
             market: market.key(),
-            authority: market.authority,
+            resolver: ctx.accounts.resolver.key(),
             winning_side,
-            pool_yes: market.pool_yes,
-            pool_no: market.pool_no,
-            no_winner,
-            fees_accrued: market.fees_accrued,
         });
+
+        let n = vote.resolver_count as usize;
+        let yes = vote.votes[..n].iter().filter(|v| **v == 0).count();
+        let no = vote.votes[..n].iter().filter(|v| **v == 1).count();
+        let pending = n - yes - no;
+        let quorum = vote.quorum as usize;
+        let outcome = if yes >= quorum {
+            Some(0)
+        } else if no >= quorum {
+            Some(1)
+        } else if yes + pending < quorum && no + pending < quorum {
+            Some(SIDE_VOID) // tie / deadlock
+        } else {
+            None
+        };
+        if let Some(side) = outcome {
+            market.resolved = true;
+            market.resolved_at = now;
+            market.resolution_source = ResolutionSource::Quorum;
+            apply_resolution(market, side)?;
+        }
+        Ok(())
+    }
+
+    /// Void a market whose resolution vote timed out without quorum. Permissionless.
+    pub fn expire_resolution_vote(ctx: Context<ExpireResolutionVote>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(market.frozen, BetError::MarketNotFrozen);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= vote_deadline(&ctx.accounts.vote)?, BetError::VoteStillOpen);
+        market.resolved = true;
+        market.resolved_at = now;
+        market.resolution_source = ResolutionSource::Quorum;
+        apply_resolution(market, SIDE_VOID)?;
+        Ok(())
+    }
+
+    /// Void (full refunds) a standard market still unresolved RESOLUTION_TIMEOUT_SECS after betting
+    /// closed (frozen, or past `close_ts`), e.g. a quorum market whose vote never opened. Permissionless.
+    pub fn void_stale_market(ctx: Context<VoidStaleMarket>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(market.kind == MarketKind::Standard, BetError::OracleMarket);
+        let now = Clock::get()?.unix_timestamp;
+        require!(market.is_stale(now), BetError::ResolveTooEarly);
+        market.frozen = true;
+        market.resolved = true;
+        market.resolved_at = now;
+        market.resolution_source = ResolutionSource::Timeout;
+        apply_resolution(market, SIDE_VOID)?;
+        Ok(())
+    }
+
+    /// Resolve an oracle market from its configured feed. Permissionless once `resolve_ts` has passed.
+    /// YES (0) wins if the observed value is strictly above the threshold, otherwise NO (1). If no
+    /// observation lands within FEED_MAX_DELAY_SECS of `resolve_ts`, the market is voided (full refunds).
+    pub fn resolve_from_feed(ctx: Context<ResolveFromFeed>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.kind == MarketKind::Oracle, BetError::NotOracleMarket);
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(ctx.accounts.feed.key() == market.oracle_feed, BetError::FeedMismatch);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= market.resolve_ts, BetError::ResolveTooEarly);
+
+        let data = ctx.accounts.feed.try_borrow_data()?;
+        let observation = feed::parse(&data).ok_or(BetError::InvalidFeed)?;
+        drop(data);
+        let window_end = market.resolve_ts.checked_add(FEED_MAX_DELAY_SECS).ok_or(BetError::MathOverflow)?;
+        let in_window = observation.updated_at >= market.resolve_ts && observation.updated_at <= window_end;
+        let side = if in_window {
+            if observation.value > market.oracle_threshold { 0 } else { 1 }
+        } else {
+            // Not yet observed: wait for the feed until the window closes, then give up and refund.
+            require!(now > window_end, BetError::FeedStale);
+            SIDE_VOID
+        };
+        market.frozen = true;
+        market.resolved = true;
+        market.resolved_at = now;
+        market.resolution_source = ResolutionSource::Oracle;
+        market.confidence_bps = if side == SIDE_VOID { 0 } else { 10_000 };
+        // Evidence for oracle markets is the raw observation: value (LE) then updated_at (LE).
+        market.evidence_hash = [0; 32];
+        market.evidence_hash[..8].copy_from_slice(&observation.value.to_le_bytes());
+        market.evidence_hash[8..16].copy_from_slice(&observation.updated_at.to_le_bytes());
+        apply_resolution(market, side)?;
+        Ok(())
+    }
+
+    /// Challenge a resolution during the dispute window by posting the market's bond. Bettors only.
+    pub fn challenge_resolution(ctx: Context<ChallengeResolution>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.resolved, BetError::MarketNotResolved);
+        require!(market.dispute_window_secs > 0, BetError::DisputesDisabled);
+        require!(market.winning_side <= 1, BetError::InvalidWinningSide);
+        require!(market.dispute_state <= DISPUTE_CHALLENGED, BetError::DisputeAlreadySettled);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < dispute_deadline(market)?, BetError::DisputeWindowClosed);
+        require!(ctx.accounts.ticket.amount > 0, BetError::ZeroAmount);
+
+        // Bond lamports sit in the bond PDA (on top of its rent) until the arbiter rules.
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.challenger.to_account_info(),
+                to: ctx.accounts.bond.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, market.dispute_bond)?;
+
+        let bond = &mut ctx.accounts.bond;
+        bond.market = market.key();
+        bond.challenger = ctx.accounts.challenger.key();
+        bond.amount = market.dispute_bond;
+        bond.bump = ctx.bumps.bond;
+
+        market.dispute_state = DISPUTE_CHALLENGED;
+        market.challenge_count = market.challenge_count.checked_add(1).ok_or(BetError::MathOverflow)?;
+        market.open_bonds = market.open_bonds.checked_add(1).ok_or(BetError::MathOverflow)?;
+        emit!(MarketChallengedEvent {
+            market: market.key(),
+            challenger: bond.challenger,
+            bond: bond.amount,
+            challenge_count: market.challenge_count,
+        });
+        Ok(())
+    }
+
+    /// Arbiter ruling on a challenged resolution. Overturning flips the winning side. Only until
+    /// the arbitration deadline; after it `expire_dispute` applies the default outcome.
+    pub fn arbitrate_dispute(ctx: Context<ArbitrateDispute>, overturn: bool) -> Result<()> {
+        let arbiter_expected = Pubkey::from_str(ARBITER_PUBKEY).map_err(|_| BetError::Unauthorized)?;
+        require!(ctx.accounts.arbiter.key() == arbiter_expected, BetError::Unauthorized);
+        let market = &mut ctx.accounts.market;
+        require!(market.dispute_state == DISPUTE_CHALLENGED, BetError::NotChallenged);
+        require!(Clock::get()?.unix_timestamp < market.arbitration_deadline(), BetError::ArbitrationExpired);
+        if overturn {
+            revert_resolution(market)?;
+            market.resolution_source = ResolutionSource::Arbiter;
+            apply_resolution(market, 1 - market.winning_side)?;
+            market.dispute_state = DISPUTE_OVERTURNED;
+        } else {
+            market.dispute_state = DISPUTE_UPHELD;
+        }
+        // The ruling makes the market final: winners get the full claim window from now.
+        set_claim_deadline(market, Clock::get()?.unix_timestamp)?;
+        emit!(DisputeArbitratedEvent {
+            market: market.key(),
+            overturned: overturn,
+            winning_side: market.winning_side,
+        });
+        Ok(())
+    }
+
+    /// Keep the challenged result once the arbiter has let the arbitration deadline pass without
+    /// ruling; challengers get their bonds back via `settle_dispute_bond`. Permissionless.
+    pub fn expire_dispute(ctx: Context<ExpireDispute>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.dispute_state == DISPUTE_CHALLENGED, BetError::NotChallenged);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= market.arbitration_deadline(), BetError::ArbitrationOpen);
+        market.dispute_state = DISPUTE_EXPIRED;
+        set_claim_deadline(market, now)?;
+        Ok(())
+    }
+
+    /// Settle a challenge bond after arbitration: slashed into the market's fees if the arbiter
+    /// upheld the result, otherwise (overturned, or no ruling in time) returned in full. Bond rent
+    /// always returns to the challenger.
+    pub fn settle_dispute_bond(ctx: Context<SettleDisputeBond>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.dispute_state > DISPUTE_CHALLENGED, BetError::DisputeUnsettled);
+        if market.dispute_state == DISPUTE_UPHELD {
+            let amount = ctx.accounts.bond.amount;
+            **ctx.accounts.bond.to_account_info().try_borrow_mut_lamports()? = ctx
+                .accounts
+                .bond
+                .to_account_info()
+                .lamports()
+                .checked_sub(amount)
+                .ok_or(BetError::InsufficientEscrow)?;
+            **market.to_account_info().try_borrow_mut_lamports()? = market
+                .to_account_info()
+                .lamports()
+                .checked_add(amount)
+                .ok_or(BetError::MathOverflow)?;
+            market.fees_accrued = market.fees_accrued.checked_add(amount).ok_or(BetError::MathOverflow)?;
+        }
+        market.open_bonds = market.open_bonds.checked_sub(1).ok_or(BetError::MathOverflow)?;
+        // Anchor closes the bond account to the challenger.
         Ok(())
     }
 
@@ -150,23 +544,61 @@ pub mod punt_program {
     pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
         let market = &mut ctx.accounts.market;
         require!(market.resolved, BetError::MarketNotResolved);
+        require_final(market)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(!market.prize_draw_pending(now), BetError::PrizeDrawPending);
+        require!(!market.claims_expired(now), BetError::ClaimExpired);
         let ticket = &mut ctx.accounts.ticket;
         require!(!ticket.claimed, BetError::AlreadyClaimed);
-        require!(ticket.side == market.winning_side, BetError::TicketSideMismatch);
+        let refund = market.refunds_stakes();
+        require!(refund || ticket.side == market.winning_side, BetError::TicketSideMismatch);
 
-        let total_pool = market.pool_yes.checked_add(market.pool_no).ok_or(BetError::MathOverflow)?;
-        // Winner share = (ticket.amount / winning_pool) * total_pool
-        let winning_pool = match market.winning_side { 0 => market.pool_yes, 1 => market.pool_no, _ => 0 };
-        require!(winning_pool > 0, BetError::MathOverflow);
-        // Compute gross payout (pro rata)
-        let numerator = (ticket.amount as u128) * (total_pool as u128);
-        let gross = (numerator / (winning_pool as u128)) as u64; // safe since pools are u64
+        let counted = ticket.amount > 0;
+        let (gross, payout, total_fee) = if refund {
+            // No contest (or no winners under NoWinnerPolicy::Refund): every ticket reclaims its full stake, fee-free.
+            (ticket.amount, ticket.amount, 0)
+        } else {
+            // A pool rolled in from an earlier no-winner market is shared by this market's winners.
+            let total_pool = market
+                .pool_yes
+                .checked_add(market.pool_no)
+                .and_then(|p| p.checked_add(market.bonus_pool))
+                .ok_or(BetError::MathOverflow)?;
+            // Winner share = (ticket.amount / winning_pool) * total_pool
+            let (winning_pool, winners) = match market.winning_side {
+                0 => (market.pool_yes, market.bettors_yes),
+                1 => (market.pool_no, market.bettors_no),
+                _ => (0, 0),
+            };
+            // The final staked winning claim receives the exact remainder of the pool (no rounding dust).
+            let is_last = counted && market.claimed_count.checked_add(1).ok_or(BetError::MathOverflow)? == winners;
+            let gross = math::settled_gross(ticket.amount, total_pool, winning_pool, market.claimed_total, is_last)
+                .ok_or(BetError::MathOverflow)?;
+            // Fees on winnings above principal only (profit portion) split authority + host
+            let (payout, total_fee) = math::net_payout(gross, ticket.amount, market.fee_bps, market.host_fee_bps)
+                .ok_or(BetError::MathOverflow)?;
+            (gross, payout, total_fee)
+        };
 
-    // Fees on winnings above principal only (profit portion) split authority + host
-    let profit = gross.checked_sub(ticket.amount).ok_or(BetError::MathOverflow)?;
-    let total_fee_bps = market.fee_bps as u64 + market.host_fee_bps as u64;
-    let total_fee = if total_fee_bps > 0 { (profit as u128 * total_fee_bps as u128 / 10_000u128) as u64 } else { 0 };
-    let payout = gross.checked_sub(total_fee).ok_or(BetError::MathOverflow)?;
+        // The referrer's cut comes out of the fee (the bettor's payout is unchanged) and moves to their vault.
+        let referral = if ticket.referrer != Pubkey::default() {
+            math::referral_share(total_fee, market.referral_bps).ok_or(BetError::MathOverflow)?
+        } else {
+            0
+        };
+        if referral > 0 {
+            let vault = ctx.accounts.referrer_vault.as_mut().ok_or(BetError::ReferrerVaultMissing)?;
+            **market.to_account_info().try_borrow_mut_lamports()? = market
+                .to_account_info()
+                .lamports()
+                .checked_sub(referral)
+                .ok_or(BetError::InsufficientEscrow)?;
+            **vault.to_account_info().try_borrow_mut_lamports()? =
+                vault.to_account_info().lamports().checked_add(referral).ok_or(BetError::MathOverflow)?;
+            vault.accrued = vault.accrued.checked_add(referral).ok_or(BetError::MathOverflow)?;
+            vault.total_earned = vault.total_earned.checked_add(referral).ok_or(BetError::MathOverflow)?;
+        }
+        let total_fee = total_fee - referral;
 
         // Transfer lamports from market escrow to user
         **market.to_account_info().try_borrow_mut_lamports()? = market
@@ -183,6 +615,10 @@ pub mod punt_program {
             .ok_or(BetError::MathOverflow)?;
 
         ticket.claimed = true;
+        if counted {
+            market.claimed_count = market.claimed_count.checked_add(1).ok_or(BetError::MathOverflow)?;
+            market.claimed_total = market.claimed_total.checked_add(gross).ok_or(BetError::MathOverflow)?;
+        }
         if total_fee > 0 {
             market.fees_accrued = market
                 .fees_accrued
@@ -192,58 +628,187 @@ pub mod punt_program {
         Ok(())
     }
 
-    /// Close a resolved (claimed or losing) ticket returning rent to user.
+    /// Close a resolved (claimed, losing or expired) ticket returning rent to user.
     pub fn close_ticket(ctx: Context<CloseTicket>) -> Result<()> {
         let market = &ctx.accounts.market;
         require!(market.resolved, BetError::MarketNotResolved);
+        // A losing ticket may still win if the result is overturned.
+        require_final(market)?;
         let ticket = &ctx.accounts.ticket;
-        // Prevent prematurely closing an unclaimed winning ticket
-        if ticket.side == market.winning_side && !ticket.claimed {
+        // Prevent prematurely closing an unclaimed winning ticket (its payout is gone once swept).
+        // A ticket that never staked is owed nothing whatever its side.
+        let owed = ticket.amount > 0 && (ticket.side == market.winning_side || market.refunds_stakes());
+        if owed && !ticket.claimed && !market.unclaimed_swept {
             return err!(BetError::CannotCloseActiveTicket);
         }
         // Anchor handles lamport return via close attribute.
         Ok(())
     }
 
-    /// Withdraw accumulated fees to authority.
+    /// Close a ticket whose market has already been closed, returning its rent to the owner.
+    /// `close_market` requires every payout to be settled first, so anything left behind is a
+    /// losing or expired ticket with nothing owed. The address may since hold a new market under
+    /// the same id, which the ticket's `market_serial` tells apart. Anyone may send it.
+    pub fn close_orphan_ticket(ctx: Context<CloseOrphanTicket>) -> Result<()> {
+        let market = ctx.accounts.market.to_account_info();
+        // A closed account is handed back to the system program with no data.
+        let closed = market.owner == &system_program::ID && market.data_is_empty();
+        let replaced = !closed
+            && market.owner == &crate::ID
+            && BetMarket::try_deserialize(&mut &market.try_borrow_data()?[..])
+                .is_ok_and(|m| m.serial != ctx.accounts.ticket.market_serial);
+        require!(closed || replaced, BetError::MarketStillOpen);
+        // Anchor handles lamport return via close attribute.
+        Ok(())
+    }
+
+    /// After the claim deadline, move every unclaimed payout out of escrow per the market's
+    /// published `UnclaimedPolicy` (treasury or authority). Unclaimed tickets are then expired:
+    /// they can no longer claim and may be closed for their rent. Anyone may send it.
+    pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.resolved, BetError::MarketNotResolved);
+        require_final(market)?;
+        require!(market.claims_expired(Clock::get()?.unix_timestamp), BetError::ClaimWindowOpen);
+        require!(!market.unclaimed_swept, BetError::AlreadySwept);
+        let rent_min = Rent::get()?.minimum_balance(8 + BetMarket::SIZE);
+        // Everything above rent that is not fees or a pending rollover is owed to unclaimed tickets.
+        let amount = market
+            .to_account_info()
+            .lamports()
+            .checked_sub(rent_min)
+            .and_then(|l| l.checked_sub(market.fees_accrued))
+            .and_then(|l| l.checked_sub(market.rollover_pending))
+            .ok_or(BetError::InsufficientEscrow)?;
+        market.unclaimed_swept = true;
+        if amount > 0 {
+            **market.to_account_info().try_borrow_mut_lamports()? = market
+                .to_account_info()
+                .lamports()
+                .checked_sub(amount)
+                .ok_or(BetError::InsufficientEscrow)?;
+            match market.unclaimed_policy {
+                UnclaimedPolicy::Treasury => credit_treasury(market, &mut ctx.accounts.treasury, amount)?,
+                UnclaimedPolicy::Authority => {
+                    **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? = ctx
+                        .accounts
+                        .authority
+                        .to_account_info()
+                        .lamports()
+                        .checked_add(amount)
+                        .ok_or(BetError::MathOverflow)?;
+                }
+            }
+        }
+        emit!(UnclaimedSweptEvent { market: market.key(), amount, policy: market.unclaimed_policy });
+        Ok(())
+    }
+
+    /// Split the authority's share of future fees across up to MAX_FEE_RECIPIENTS recipients
+    /// (co-hosts, a charity, ...). Weights are bps of that share and must sum to 100%; the
+    /// rounding remainder goes to `recipients[remainder_index]`. The host share is unaffected.
+    /// A share too small to leave its (unfunded) recipient rent-exempt goes to the remainder
+    /// recipient instead, or to the treasury if that one cannot take it either.
+    /// Fixed before the first bet so bettors and recipients see the final split.
+    pub fn set_fee_schedule(
+        ctx: Context<SetFeeSchedule>,
+        recipients: Vec<Pubkey>,
+        weights: Vec<u16>,
+        remainder_index: u8,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(market.pool_yes == 0 && market.pool_no == 0, BetError::MarketHasBets);
+        let n = recipients.len();
+        require!(n > 0 && n <= MAX_FEE_RECIPIENTS && weights.len() == n, BetError::InvalidFeeSchedule);
+        require!((remainder_index as usize) < n, BetError::InvalidFeeSchedule);
+        for (i, r) in recipients.iter().enumerate() {
+            require!(*r != Pubkey::default() && !recipients[..i].contains(r), BetError::InvalidFeeSchedule);
+        }
+        let total: u64 = weights.iter().map(|w| *w as u64).sum();
+        require!(total == math::BPS_DENOMINATOR, BetError::InvalidFeeSchedule);
+        let schedule = &mut ctx.accounts.fee_schedule;
+        schedule.market = market.key();
+        schedule.recipients = [Pubkey::default(); MAX_FEE_RECIPIENTS];
+        schedule.recipients[..n].copy_from_slice(&recipients);
+        schedule.weights = [0; MAX_FEE_RECIPIENTS];
+        schedule.weights[..n].copy_from_slice(&weights);
+        schedule.recipient_count = n as u8;
+        schedule.remainder_index = remainder_index;
+        schedule.bump = ctx.bumps.fee_schedule;
+        market.has_fee_schedule = true;
+        Ok(())
+    }
+
+    /// Withdraw accumulated fees: the host share into the platform treasury, the authority share to
+    /// the authority or, with a fee schedule, to its recipients (remaining accounts in schedule order).
+    /// Anyone may crank it once every payout has been claimed.
     pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
         let market = &mut ctx.accounts.market;
-        let host_expected = Pubkey::from_str(HOST_PUBKEY).map_err(|_| BetError::Unauthorized)?;
-        require!(ctx.accounts.host.key() == host_expected, BetError::Unauthorized);
+        require_crank_allowed(market, &ctx.accounts.caller.key())?;
+        require!(ctx.accounts.fee_schedule.is_some() == market.has_fee_schedule, BetError::FeeScheduleMismatch);
+        if market.resolved { require_final(market)?; }
         let amount = market.fees_accrued;
         require!(amount > 0, BetError::ZeroAmount);
-        let total_bps = market.fee_bps as u64 + market.host_fee_bps as u64;
-        let authority_share = if total_bps > 0 { (amount as u128 * market.fee_bps as u128 / total_bps as u128) as u64 } else { amount };
-        let host_share = amount.checked_sub(authority_share).ok_or(BetError::MathOverflow)?;
+        let (authority_share, host_share) = math::split_fees(amount, market.fee_bps, market.host_fee_bps);
         **market.to_account_info().try_borrow_mut_lamports()? = market
             .to_account_info()
             .lamports()
             .checked_sub(amount)
             .ok_or(BetError::InsufficientEscrow)?;
-        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? = ctx
-            .accounts
-            .authority
-            .to_account_info()
-            .lamports()
-            .checked_add(authority_share)
-            .ok_or(BetError::MathOverflow)?;
-        **ctx.accounts.host.to_account_info().try_borrow_mut_lamports()? = ctx
+        let unpaid = pay_authority_share(
+            &ctx.accounts.authority.to_account_info(),
+            ctx.accounts.fee_schedule.as_deref(),
+            ctx.remaining_accounts,
+            authority_share,
+        )?;
+        credit_treasury(market, &mut ctx.accounts.treasury, host_share + unpaid)?;
+        market.fees_accrued = 0;
+        Ok(())
+    }
+
+    /// Open the vault in which `referrer` accrues referral rewards; needed before tickets can name them.
+    pub fn init_referrer_vault(ctx: Context<InitReferrerVault>) -> Result<()> {
+        let vault = &mut ctx.accounts.referrer_vault;
+        vault.referrer = ctx.accounts.referrer.key();
+        vault.accrued = 0;
+        vault.total_earned = 0;
+        vault.bump = ctx.bumps.referrer_vault;
+        Ok(())
+    }
+
+    /// Pay the referrer everything accrued in their vault (the vault keeps its rent).
+    pub fn withdraw_referral_rewards(ctx: Context<WithdrawReferralRewards>) -> Result<()> {
+        let vault = &mut ctx.accounts.referrer_vault;
+        let amount = vault.accrued;
+        require!(amount > 0, BetError::ZeroAmount);
+        **vault.to_account_info().try_borrow_mut_lamports()? =
+            vault.to_account_info().lamports().checked_sub(amount).ok_or(BetError::InsufficientEscrow)?;
+        **ctx.accounts.referrer.to_account_info().try_borrow_mut_lamports()? = ctx
             .accounts
-            .host
+            .referrer
             .to_account_info()
             .lamports()
-            .checked_add(host_share)
+            .checked_add(amount)
             .ok_or(BetError::MathOverflow)?;
-        market.fees_accrued = 0;
+        vault.accrued = 0;
         Ok(())
     }
 
-    /// Close a resolved market returning rent to authority. All winnings must be claimed and fees withdrawn.
+    /// Close a resolved market returning rent to authority. All winnings must be claimed, fees withdrawn
+    /// and dispute bonds settled.
+    /// Anyone may crank it once every payout has been claimed; such a caller is tipped
+    /// CRANK_TIP_LAMPORTS out of the market's rent, the rest of which still goes to the authority.
     pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
         let market = &mut ctx.accounts.market;
-        let host_expected = Pubkey::from_str(HOST_PUBKEY).map_err(|_| BetError::Unauthorized)?;
-        require!(ctx.accounts.host.key() == host_expected, BetError::Unauthorized);
+        let caller = ctx.accounts.caller.key();
+        require_crank_allowed(market, &caller)?;
         require!(market.resolved, BetError::MarketNotResolved);
+        require_final(market)?;
+        require!(market.rollover_pending == 0, BetError::RolloverPending);
+        // A settled bond can still slash into fees, and it needs the market to exist to settle at all.
+        require!(market.open_bonds == 0, BetError::BondsOutstanding);
+        require!(ctx.accounts.fee_schedule.is_some() == market.has_fee_schedule, BetError::FeeScheduleMismatch);
         let rent_min = Rent::get()?.minimum_balance(8 + BetMarket::SIZE);
         let current = market.to_account_info().lamports();
     // Salvage path (legacy / redundancy guard):
@@ -251,45 +816,257 @@ pub mod punt_program {
     // With current logic, resolve_market already accrues the entire losing pool into fees_accrued when the
     // winning side had zero bets, so this branch should rarely (ideally never) trigger except for historical
     // markets resolved before the upgrade. Keeping it as a safety net; can be removed after a migration window.
-    // If triggered, it directly disburses remaining lamports above rent to authority+host according to fee split.
+    // If triggered, it disburses remaining lamports above rent like withdraw_fees: the authority share to the
+    // authority (or its fee schedule's recipients, passed as remaining accounts), the host share to the treasury.
         let winning_pool = match market.winning_side { 0 => market.pool_yes, 1 => market.pool_no, _ => 0 };
-        if winning_pool == 0 && current > rent_min {
+        if market.no_winner_policy == NoWinnerPolicy::Fees && market.winning_side <= 1 && winning_pool == 0 && current > rent_min {
             let distributable = current.checked_sub(rent_min).ok_or(BetError::MathOverflow)?;
-            let total_bps = market.fee_bps as u64 + market.host_fee_bps as u64;
-            let authority_share = if total_bps > 0 { (distributable as u128 * market.fee_bps as u128 / total_bps as u128) as u64 } else { distributable };
-            let host_share = distributable.checked_sub(authority_share).unwrap_or(0);
+            let (authority_share, host_share) = math::split_fees(distributable, market.fee_bps, market.host_fee_bps);
             // deduct
             **market.to_account_info().try_borrow_mut_lamports()? = current - distributable;
-            **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.authority.to_account_info().lamports().checked_add(authority_share).ok_or(BetError::MathOverflow)?;
-            **ctx.accounts.host.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.host.to_account_info().lamports().checked_add(host_share).ok_or(BetError::MathOverflow)?;
+            let unpaid = pay_authority_share(
+                &ctx.accounts.authority.to_account_info(),
+                ctx.accounts.fee_schedule.as_deref(),
+                ctx.remaining_accounts,
+                authority_share,
+            )?;
+            credit_treasury(market, &mut ctx.accounts.treasury, host_share + unpaid)?;
         }
         // After salvage, ensure no pending fee accruals.
         require!(market.fees_accrued == 0, BetError::FeesRemaining);
-        // Allow a small "dust" remainder (e.g. from integer division truncation in earlier versions) to be swept now.
-        const DUST_MAX: u64 = 10; // up to 10 lamports tolerance
-        let after = market.to_account_info().lamports();
-        if after > rent_min {
-            let extra = after.checked_sub(rent_min).ok_or(BetError::MathOverflow)?;
-            if extra <= DUST_MAX {
-                // Split dust proportionally using same fee weights (fall back to authority if total_bps == 0)
-                let total_bps = market.fee_bps as u64 + market.host_fee_bps as u64;
-                let authority_share = if total_bps > 0 { (extra as u128 * market.fee_bps as u128 / total_bps as u128) as u64 } else { extra };
-                let host_share = extra.checked_sub(authority_share).unwrap_or(0);
-                **market.to_account_info().try_borrow_mut_lamports()? = after.checked_sub(extra).ok_or(BetError::MathOverflow)?;
-                **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.authority.to_account_info().lamports().checked_add(authority_share).ok_or(BetError::MathOverflow)?;
-                **ctx.accounts.host.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.host.to_account_info().lamports().checked_add(host_share).ok_or(BetError::MathOverflow)?;
-            }
-        }
+        // Claims settle the pool exactly (last winner takes the remainder), so nothing may remain above rent.
         require!(market.to_account_info().lamports() == rent_min, BetError::OutstandingLamports);
+        if caller != market.authority {
+            **market.to_account_info().try_borrow_mut_lamports()? = rent_min - CRANK_TIP_LAMPORTS.min(rent_min);
+            **ctx.accounts.caller.to_account_info().try_borrow_mut_lamports()? = ctx
+                .accounts
+                .caller
+                .to_account_info()
+                .lamports()
+                .checked_add(CRANK_TIP_LAMPORTS.min(rent_min))
+                .ok_or(BetError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// Create the platform treasury that collects the host fee share of every market.
+    pub fn init_treasury(ctx: Context<InitTreasury>) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.collected = 0;
+        treasury.withdrawn = 0;
+        treasury.bump = ctx.bumps.treasury;
+        treasury.admin = Pubkey::from_str(TREASURY_ADMIN_PUBKEY).map_err(|_| BetError::Unauthorized)?;
+        Ok(())
+    }
+
+    /// Hand the treasury to a new admin key; only the current `Treasury::admin` may sign.
+    pub fn set_treasury_admin(ctx: Context<SetTreasuryAdmin>, new_admin: Pubkey) -> Result<()> {
+        require!(new_admin != Pubkey::default(), BetError::Unauthorized);
+        ctx.accounts.treasury.admin = new_admin;
+        Ok(())
+    }
+
+    /// Pay `amount` of collected host fees out of the treasury; only `Treasury::admin` may sign.
+    pub fn treasury_withdraw(ctx: Context<TreasuryWithdraw>, amount: u64) -> Result<()> {
+        require!(amount > 0, BetError::ZeroAmount);
+        let treasury = &mut ctx.accounts.treasury;
+        let available = treasury.collected.checked_sub(treasury.withdrawn).ok_or(BetError::MathOverflow)?;
+        require!(amount <= available, BetError::InsufficientEscrow);
+        **treasury.to_account_info().try_borrow_mut_lamports()? =
+            treasury.to_account_info().lamports().checked_sub(amount).ok_or(BetError::InsufficientEscrow)?;
+        **ctx.accounts.destination.to_account_info().try_borrow_mut_lamports()? = ctx
+            .accounts
+            .destination
+            .to_account_info()
+            .lamports()
+            .checked_add(amount)
+            .ok_or(BetError::MathOverflow)?;
+        treasury.withdrawn = treasury.withdrawn.checked_add(amount).ok_or(BetError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Move a final market's unclaimable pool (`rollover_pending`) into a later, unresolved market
+    /// of the same authority, where it is paid out to that market's winners on top of their pool.
+    pub fn roll_over_pool(ctx: Context<RollOverPool>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let destination = &mut ctx.accounts.destination;
+        require!(market.resolved, BetError::MarketNotResolved);
+        require_final(market)?;
+        let amount = market.rollover_pending;
+        require!(amount > 0, BetError::ZeroAmount);
+        require!(destination.cycle > market.cycle, BetError::InvalidRolloverTarget);
+        require!(!destination.resolved, BetError::MarketAlreadyResolved);
+
+        **market.to_account_info().try_borrow_mut_lamports()? = market
+            .to_account_info()
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(BetError::InsufficientEscrow)?;
+        **destination.to_account_info().try_borrow_mut_lamports()? = destination
+            .to_account_info()
+            .lamports()
+            .checked_add(amount)
+            .ok_or(BetError::MathOverflow)?;
+        market.rollover_pending = 0;
+        destination.bonus_pool = destination.bonus_pool.checked_add(amount).ok_or(BetError::MathOverflow)?;
+        emit!(PoolRolledOverEvent { from: market.key(), to: destination.key(), amount });
+        Ok(())
+    }
+
+    /// Send a no-winner pool the authority never rolled over to the treasury once the rollover
+    /// deadline has passed, so a market without any later market can still close. Anyone may send it.
+    pub fn expire_rollover(ctx: Context<ExpireRollover>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.resolved, BetError::MarketNotResolved);
+        require_final(market)?;
+        require!(Clock::get()?.unix_timestamp >= market.rollover_deadline(), BetError::RolloverWindowOpen);
+        let amount = market.rollover_pending;
+        require!(amount > 0, BetError::ZeroAmount);
+        **market.to_account_info().try_borrow_mut_lamports()? = market
+            .to_account_info()
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(BetError::InsufficientEscrow)?;
+        market.rollover_pending = 0;
+        credit_treasury(market, &mut ctx.accounts.treasury, amount)?;
+        emit!(RolloverExpiredEvent { market: market.key(), amount });
+        Ok(())
+    }
+
+    /// Replace title and labels (e.g. to fix typos). Only allowed before the first bet and while not frozen.
+    pub fn update_market_metadata(
+        ctx: Context<UpdateMarketMetadata>,
+        title: String,
+        label_yes: String,
+        label_no: String,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(!market.frozen, BetError::MarketFrozen);
+        require!(market.pool_yes == 0 && market.pool_no == 0, BetError::MarketHasBets);
+        write_market_text(market, &title, &label_yes, &label_no)?;
         Ok(())
     }
 
-    /// Freeze the market to stop further betting prior to resolution.
+    /// Freeze the market to stop further betting prior to resolution. A committed prize draw takes
+    /// its entropy (the newest slot hash) here, once no further bet can land.
     pub fn freeze_market(ctx: Context<FreezeMarket>) -> Result<()> {
         let market = &mut ctx.accounts.market;
         require!(!market.resolved, BetError::MarketAlreadyResolved);
         require!(!market.frozen, BetError::MarketAlreadyFrozen);
         market.frozen = true;
+        market.frozen_at = Clock::get()?.unix_timestamp;
+        if market.prize_seed_hash != [0; 32] {
+            let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+            // SlotHashes layout: u64 entry count, then (slot u64, hash [u8; 32]) newest first.
+            require!(slot_hashes.len() >= 48, BetError::PrizeEntrantsInvalid);
+            market.prize_entropy.copy_from_slice(&slot_hashes[16..48]);
+        }
+        Ok(())
+    }
+
+    /// Reopen a frozen (unresolved) market for betting. Limited to MAX_REOPENS per market.
+    pub fn unfreeze_market(ctx: Context<FreezeMarket>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(market.frozen, BetError::MarketNotFrozen);
+        require!(market.reopen_count < MAX_REOPENS, BetError::ReopenLimitReached);
+        market.frozen = false;
+        market.frozen_at = 0;
+        market.prize_entropy = [0; 32]; // re-read at the next freeze
+        market.reopen_count += 1;
+        emit!(MarketUnfrozenEvent {
+            market: market.key(),
+            authority: market.authority,
+            reopen_count: market.reopen_count,
+        });
+        Ok(())
+    }
+
+    /// Commit to a prize-draw seed (`sha256(seed)`) and the draw weighting. Only before the freeze,
+    /// and only once, so the authority cannot pick a seed after seeing the bets. The draw's entropy
+    /// is taken at `freeze_market`, after the last bet, so the seed cannot be ground against it
+    /// either. Standard markets only: oracle markets resolve without a freeze.
+    pub fn commit_prize_seed(ctx: Context<CommitPrizeSeed>, seed_hash: [u8; 32], weighting: PrizeWeighting) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.kind == MarketKind::Standard, BetError::OracleMarket);
+        require!(!market.resolved, BetError::MarketAlreadyResolved);
+        require!(!market.frozen, BetError::MarketFrozen);
+        require!(market.prize_seed_hash == [0; 32], BetError::PrizeAlreadyCommitted);
+        require!(seed_hash != [0; 32], BetError::PrizeSeedMismatch);
+        market.prize_seed_hash = seed_hash;
+        market.prize_weighting = weighting;
+        Ok(())
+    }
+
+    /// Reveal the committed seed and walk one page of the winning tickets, weighted per ticket or by
+    /// stake. Permissionless: anyone holding the seed may send it. `remaining_accounts` holds the
+    /// next staked winning tickets in entry order (`BetTicket::entry_index`), continuing exactly
+    /// where the last page stopped, so the walk cannot be restarted or reordered. The winner is recorded once every
+    /// winning ticket has been walked. Claims are held back until the draw (or until the reveal
+    /// window lapses) so none are closed early.
+    pub fn draw_prize_winner<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DrawPrizeWinner<'info>>,
+        seed: [u8; 32],
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        require!(market.prize_seed_hash != [0; 32], BetError::NoPrizeCommitted);
+        require!(market.prize_winner == Pubkey::default(), BetError::PrizeAlreadyDrawn);
+        require!(market.resolved, BetError::MarketNotResolved);
+        require_final(market)?;
+        require!(hash(&seed).to_bytes() == market.prize_seed_hash, BetError::PrizeSeedMismatch);
+        let (winning_pool, winners) = match market.winning_side {
+            0 => (market.pool_yes, market.bettors_yes),
+            1 => (market.pool_no, market.bettors_no),
+            _ => (0, 0),
+        };
+        require!(winners > 0, BetError::NoPrizeEntrants);
+
+        let market_key = market.key();
+        let total_weight = match market.prize_weighting {
+            PrizeWeighting::PerTicket => winners as u64,
+            PrizeWeighting::ByStake => winning_pool,
+        };
+        let mix = hashv(&[&seed, &market.prize_entropy, market_key.as_ref()]).to_bytes();
+        // Widening multiply instead of `% total_weight`: maps the u64 onto [0, total_weight) without modulo bias.
+        let draw = u64::from_le_bytes(mix[..8].try_into().unwrap());
+        let target = ((draw as u128 * total_weight as u128) >> 64) as u64;
+
+        for info in ctx.remaining_accounts {
+            let ticket = Account::<BetTicket>::try_from(info)?;
+            require_keys_eq!(ticket.market, market_key, BetError::TicketMarketMismatch);
+            require!(ticket.market_serial == market.serial, BetError::TicketMarketMismatch);
+            require!(ticket.side == market.winning_side && ticket.amount > 0, BetError::TicketSideMismatch);
+            require!(ticket.entry_index == market.prize_counted, BetError::PrizeEntrantsInvalid);
+            // Weight walked so far: the entry range of this ticket is [seen, seen + weight).
+            let (seen, weight) = match market.prize_weighting {
+                PrizeWeighting::PerTicket => (market.prize_counted as u64, 1),
+                PrizeWeighting::ByStake => (market.prize_staked, ticket.amount),
+            };
+            if seen <= target && target - seen < weight {
+                market.prize_candidate = ticket.user;
+            }
+            market.prize_counted = market.prize_counted.checked_add(1).ok_or(BetError::MathOverflow)?;
+            market.prize_staked = market.prize_staked.checked_add(ticket.amount).ok_or(BetError::MathOverflow)?;
+            market.prize_cursor = info.key();
+        }
+        require!(
+            market.prize_counted <= winners && market.prize_staked <= winning_pool,
+            BetError::PrizeEntrantsInvalid
+        );
+        if market.prize_counted == winners {
+            require!(market.prize_staked == winning_pool, BetError::PrizeEntrantsInvalid);
+            let winner = market.prize_candidate;
+            market.prize_winner = winner;
+            let (ticket, _) = Pubkey::find_program_address(&[b"ticket", market_key.as_ref(), winner.as_ref()], &crate::ID);
+            emit!(PrizeWinnerDrawnEvent {
+                market: market_key,
+                ticket,
+                winner,
+                weighting: market.prize_weighting,
+                total_weight,
+            });
+        }
         Ok(())
     }
 }
@@ -306,30 +1083,169 @@ pub struct MarketResolvedEvent {
     pub pool_no: u64,
     pub no_winner: bool,
     pub fees_accrued: u64,
+    pub evidence_hash: [u8; 32],
+    pub confidence_bps: u16,
+    pub source: ResolutionSource,
 }
 
-// -------------------------------------------------------------------------------------------------
-// State
-// -------------------------------------------------------------------------------------------------
-pub const AUTHORITY_FEE_BPS_DEFAULT: u16 = 20; // 0.2%
-pub const HOST_FEE_BPS_DEFAULT: u16 = 670; // 6.7%
-pub const HOST_PUBKEY: &str = "9KQjnCXwNcnaojsfvuD894UjnCKvgwEDe4Kt1nfpDNHB"; // platform host wallet
-pub const RESOLVER_PUBKEY: &str = "H54atNiSJVjGtmLkSkXWRigiriXyhJjrDeXgwpi2ThTn"; // delegated AI resolver wallet
-pub const TITLE_MAX_LEN: usize = 64;
-pub const LABEL_MAX_LEN: usize = 32;
-
-#[account]
-pub struct AuthorityMeta {
-    pub authority: Pubkey,
-    pub next_cycle: u16, // next cycle index to use when initializing a new market
-    pub bump: u8,
+#[event]
+pub struct MarketChallengedEvent {
+    pub market: Pubkey,
+    pub challenger: Pubkey,
+    pub bond: u64,
+    pub challenge_count: u16,
 }
-impl AuthorityMeta { pub const SIZE: usize = 32 + 2 + 1; }
 
+#[event]
+pub struct DisputeArbitratedEvent {
+    pub market: Pubkey,
+    pub overturned: bool,
+    pub winning_side: u8,
+}
+
+#[event]
+pub struct ResolutionVoteEvent {
+    pub market: Pubkey,
+    pub resolver: Pubkey,
+    pub winning_side: u8,
+}
+
+#[event]
+pub struct PrizeWinnerDrawnEvent {
+    pub market: Pubkey,
+    pub ticket: Pubkey,
+    pub winner: Pubkey,
+    pub weighting: PrizeWeighting,
+    pub total_weight: u64,
+}
+
+#[event]
+pub struct UnclaimedSweptEvent {
+    pub market: Pubkey,
+    pub amount: u64,
+    pub policy: UnclaimedPolicy,
+}
+
+#[event]
+pub struct TreasuryDepositEvent {
+    pub market: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PoolRolledOverEvent {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RolloverExpiredEvent {
+    pub market: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MarketUnfrozenEvent {
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub reopen_count: u8,
+}
+
+// -------------------------------------------------------------------------------------------------
+// State
+// -------------------------------------------------------------------------------------------------
+pub const AUTHORITY_FEE_BPS_DEFAULT: u16 = 20; // 0.2%
+pub const HOST_FEE_BPS_DEFAULT: u16 = 670; // 6.7%
+pub const HOST_PUBKEY: &str = "9KQjnCXwNcnaojsfvuD894UjnCKvgwEDe4Kt1nfpDNHB"; // platform host wallet
+pub const RESOLVER_PUBKEY: &str = "H54atNiSJVjGtmLkSkXWRigiriXyhJjrDeXgwpi2ThTn"; // delegated AI resolver wallet
+pub const TITLE_MAX_LEN: usize = 64;
+pub const LABEL_MAX_LEN: usize = 32;
+pub const MAX_REOPENS: u8 = 3; // unfreeze_market calls allowed per market
+pub const TREASURY_ADMIN_PUBKEY: &str = "9sZtF99xEwtUNekaT9bosMnnxwjJMtxHeCDiHAyQvhs3"; // initial Treasury.admin (cold key, never a resolver)
+#[cfg(not(feature = "test-arbiter"))]
+pub const ARBITER_PUBKEY: &str = "6sFGU6auJapsCxnWjeEdcdN9UjAnxxWQSBnvxn2fwubK"; // dispute arbiter (not the host/treasury key)
+#[cfg(feature = "test-arbiter")]
+pub const ARBITER_PUBKEY: &str = "61UbBtihBRPtahLSizCULYT2HizRDY1wBfmNuqNVkwmF"; // tests/fixtures/arbiter.json; local test builds only
+pub const ARBITRATION_WINDOW_SECS: i64 = 7 * 86_400; // after the dispute window; then expire_dispute keeps the result
+pub const ROLLOVER_TIMEOUT_SECS: i64 = 30 * 86_400; // after the dispute window; then expire_rollover sends the pool to the treasury
+// BetMarket.dispute_state values
+pub const DISPUTE_NONE: u8 = 0;
+pub const DISPUTE_CHALLENGED: u8 = 1; // awaiting arbiter; claims blocked
+pub const DISPUTE_UPHELD: u8 = 2;     // arbiter kept the result; bonds slashed
+pub const DISPUTE_OVERTURNED: u8 = 3; // arbiter flipped the result; bonds returned
+pub const DISPUTE_EXPIRED: u8 = 4;    // arbiter never ruled; result kept, bonds returned
+pub const SIDE_VOID: u8 = 2; // winning_side for a no-contest market: every ticket is refunded
+pub const MAX_RESOLVERS: usize = 5;
+pub const MIN_VOTE_QUORUM: u8 = 2; // a ResolutionVote needs at least this many matching votes
+pub const RESOLUTION_TIMEOUT_SECS: i64 = 7 * 86_400; // unresolved this long after betting closed => void_stale_market
+pub const VOTE_NONE: u8 = 255; // ResolutionVote.votes sentinel for "not voted yet"
+
+// Arbitration moves winnings between sides, so it must not share a key with the treasury or host.
+const _: () = assert!(!same_key(ARBITER_PUBKEY, TREASURY_ADMIN_PUBKEY) && !same_key(ARBITER_PUBKEY, HOST_PUBKEY));
+// The host and resolver keys sign resolve_market from hot wallets; they must not also hold the treasury.
+const _: () = assert!(!same_key(TREASURY_ADMIN_PUBKEY, HOST_PUBKEY) && !same_key(TREASURY_ADMIN_PUBKEY, RESOLVER_PUBKEY));
+
+const fn same_key(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+pub const FEED_MAX_DELAY_SECS: i64 = 300; // oracle observation must land within this long after resolve_ts
+pub const MAX_FEE_RECIPIENTS: usize = 8;
+pub const CRANK_TIP_LAMPORTS: u64 = 10_000; // paid from market rent to a third party that cranks close_market
+pub const PRIZE_REVEAL_WINDOW_SECS: i64 = 86_400; // after finality, claims wait this long for draw_prize_winner
+pub const MIN_CLAIM_WINDOW_SECS: u32 = 7 * 86_400; // shortest non-zero claim window a market may set
+
+#[account]
+pub struct AuthorityMeta {
+    pub authority: Pubkey,
+    pub next_cycle: u64,     // one past the highest market id used (suggested id for sequential clients)
+    pub bump: u8,
+    pub markets_opened: u64, // markets ever created; the latest one's BetMarket::serial
+}
+impl AuthorityMeta { pub const SIZE: usize = 32 + 8 + 1 + 8; }
+
+/// `AuthorityMeta` layout before market ids widened to u64; read only by `migrate_authority_meta`.
+#[derive(AnchorDeserialize)]
+pub struct LegacyAuthorityMeta {
+    pub authority: Pubkey,
+    pub next_cycle: u16,
+    pub bump: u8,
+}
+impl LegacyAuthorityMeta { pub const SIZE: usize = 32 + 2 + 1; }
+
+/// Reusable market preset (PDA: ["template", authority_meta, id]).
+#[account]
+pub struct MarketTemplate {
+    pub authority: Pubkey,
+    pub id: u16,
+    pub title: [u8; TITLE_MAX_LEN],
+    pub label_yes: [u8; LABEL_MAX_LEN],
+    pub label_no: [u8; LABEL_MAX_LEN],
+    pub fee_bps: u16,
+    pub limits: BetLimits,
+    pub duration_secs: u32, // betting window from market creation (0 = freeze manually)
+    pub bump: u8,
+}
+impl MarketTemplate { pub const SIZE: usize = 32 + 2 + TITLE_MAX_LEN + 2 * LABEL_MAX_LEN + 2 + 3 * 8 + 4 + 1; }
+
+/// Never reallocated: markets created by an earlier release keep their old size (and, before ids
+/// widened to u64, their u16-seeded address), so layout changes require every market to be settled
+/// and closed first or a fresh deployment (see "Upgrading the program" in the README).
 #[account]
 pub struct BetMarket {
     pub authority: Pubkey,
-    pub cycle: u16, // cycle index (unique per authority)
+    pub cycle: u64, // market id chosen at creation (unique per authority)
     pub pool_yes: u64,
     pub pool_no: u64,
     pub resolved: bool,
@@ -339,12 +1255,256 @@ pub struct BetMarket {
     pub bump: u8,
     pub winning_side: u8,
     pub fees_accrued: u64,  // total (authority+host) accrued
-    pub title: [u8; TITLE_MAX_LEN],     // UTF-8 (not guaranteed validated) null-padded
+    pub title: [u8; TITLE_MAX_LEN],     // validated UTF-8 without NULs, null-padded
     pub label_yes: [u8; LABEL_MAX_LEN], // label for side 0
     pub label_no: [u8; LABEL_MAX_LEN],  // label for side 1
+    pub bettors_yes: u32,   // tickets with a non-zero stake on side 0
+    pub bettors_no: u32,    // tickets with a non-zero stake on side 1
+    pub claimed_total: u64, // gross paid out of the pool so far (payouts + fees on them)
+    pub claimed_count: u32, // staked winning tickets claimed so far
+    pub min_bet: u64,            // minimum lamports per place_bet (0 = none)
+    pub max_bet_per_ticket: u64, // cap on a ticket's cumulative stake (0 = none)
+    pub max_pool: u64,           // cap on pool_yes + pool_no (0 = none)
+    pub reopen_count: u8,        // times unfreeze_market has reopened betting
+    pub resolved_at: i64,         // unix timestamp of resolve_market (0 = unresolved)
+    pub dispute_window_secs: u32, // challenge period after resolution (0 = disputes disabled)
+    pub dispute_bond: u64,        // lamports a challenger must post
+    pub dispute_state: u8,        // DISPUTE_* value
+    pub challenge_count: u16,     // bonds posted against the current resolution
+    pub quorum_threshold: u64,    // pools at/above this require a ResolutionVote quorum (0 = never)
+    pub evidence_hash: [u8; 32],  // e.g. SHA-256 of sampled frames + OCR output (zero if none)
+    pub confidence_bps: u16,      // resolver confidence, 0..=10_000
+    pub resolution_source: ResolutionSource,
+    pub kind: MarketKind,
+    pub oracle_feed: Pubkey,      // feed account for MarketKind::Oracle (default otherwise)
+    pub resolve_ts: i64,          // oracle resolution time (0 for standard markets)
+    pub oracle_threshold: i64,    // YES iff feed value > threshold
+    pub close_ts: i64,            // scheduled betting close (0 = frozen manually)
+    pub fixed_stake: u64,         // exact one-time stake per ticket (0 = free amounts); bettors_* are then head counts
+    pub prize_seed_hash: [u8; 32], // sha256 of the committed prize-draw seed (zero = no prize draw)
+    pub prize_weighting: PrizeWeighting,
+    pub prize_winner: Pubkey,     // owner of the drawn ticket (default until drawn)
+    pub no_winner_policy: NoWinnerPolicy,
+    pub bonus_pool: u64,          // lamports rolled in from earlier no-winner markets, paid to winners
+    pub rollover_pending: u64,    // lamports waiting for roll_over_pool or expire_rollover (blocks close_market)
+    pub referral_bps: u16,        // share of each referred ticket's fee paid to its referrer
+    pub has_fee_schedule: bool,   // authority share is split per the market's FeeSchedule PDA
+    pub treasury_paid: u64,       // host fee share (and swept payouts) this market has paid into the Treasury
+    pub claim_window_secs: u32,   // claims expire this long after they open (0 = never)
+    pub claim_deadline: i64,      // set once resolved (and again by arbitration); 0 = no deadline
+    pub unclaimed_policy: UnclaimedPolicy,
+    pub unclaimed_swept: bool,    // sweep_unclaimed ran; unclaimed tickets are expired
+    pub frozen_at: i64,           // unix timestamp of freeze_market (0 while open)
+    pub prize_entropy: [u8; 32],  // newest slot hash at freeze_market (prize draws only)
+    pub prize_cursor: Pubkey,     // last winning ticket walked by draw_prize_winner
+    pub prize_counted: u32,       // winning tickets walked so far
+    pub prize_staked: u64,        // their summed stake
+    pub prize_candidate: Pubkey,  // owner of the walked ticket whose entry range holds the draw
+    pub open_bonds: u16,          // challenge bonds not yet settled (blocks close_market)
+    pub serial: u64,              // AuthorityMeta::markets_opened at creation; tells apart markets reusing an id
+}
+// SIZE (without discriminator): previous 491 + 32 + 32 + 4 + 8 + 32 (paginated prize draw) + 2 (open bonds) + 8 (serial) = 609
+impl BetMarket {
+    pub const SIZE: usize = 32 + 8 + 8 + 8 + 1 + 1 + 2 + 2 + 1 + 1 + 8 + 64 + 32 + 32 + 4 + 4 + 8 + 4 + 8 + 8 + 8 + 1 + 8 + 4 + 8 + 1 + 2 + 8 + 32 + 2 + 1 + 1 + 32 + 8 + 8 + 8 + 8 + 32 + 1 + 32 + 1 + 8 + 8 + 2 + 1 + 8 + 4 + 8 + 1 + 1 + 8 + 32 + 32 + 4 + 8 + 32 + 2 + 8;
+
+    /// Resolved and past any dispute (window elapsed unchallenged, or arbiter ruled). Off-chain
+    /// tooling uses this to know when claims, fee withdrawal and close can succeed.
+    pub fn is_final(&self, now: i64) -> bool {
+        self.resolved
+            && match self.dispute_state {
+                DISPUTE_CHALLENGED => false,
+                DISPUTE_UPHELD | DISPUTE_OVERTURNED | DISPUTE_EXPIRED => true,
+                _ => self.dispute_window_secs == 0 || now >= self.resolved_at.saturating_add(self.dispute_window_secs as i64),
+            }
+    }
+
+    /// From when expire_rollover may send a pending rollover to the treasury: ROLLOVER_TIMEOUT_SECS
+    /// after the dispute window closes, which also outlasts any arbitration.
+    pub fn rollover_deadline(&self) -> i64 {
+        self.resolved_at
+            .saturating_add(self.dispute_window_secs as i64)
+            .saturating_add(ROLLOVER_TIMEOUT_SECS)
+    }
+
+    /// Last moment the arbiter may rule on a challenge: ARBITRATION_WINDOW_SECS after the dispute
+    /// window closes, so every challenge gets the full window.
+    pub fn arbitration_deadline(&self) -> i64 {
+        self.resolved_at
+            .saturating_add(self.dispute_window_secs as i64)
+            .saturating_add(ARBITRATION_WINDOW_SECS)
+    }
+
+    /// Betting closed (frozen, or past `close_ts`) more than RESOLUTION_TIMEOUT_SECS ago without a
+    /// resolution: anyone may void the market via `void_stale_market`.
+    pub fn is_stale(&self, now: i64) -> bool {
+        let closed_at = if self.frozen {
+            self.frozen_at
+        } else if self.close_ts > 0 && now >= self.close_ts {
+            self.close_ts
+        } else {
+            return false;
+        };
+        !self.resolved && now >= closed_at.saturating_add(RESOLUTION_TIMEOUT_SECS)
+    }
+
+    /// The claim deadline has passed: unclaimed payouts can only be swept.
+    pub fn claims_expired(&self, now: i64) -> bool {
+        self.claim_deadline > 0 && now >= self.claim_deadline
+    }
+
+    /// Every staked ticket gets its stake back fee-free: a void (no-contest) result, or a winning
+    /// side without bets under `NoWinnerPolicy::Refund`.
+    pub fn refunds_stakes(&self) -> bool {
+        let refund_if_empty = self.no_winner_policy == NoWinnerPolicy::Refund;
+        match self.winning_side {
+            SIDE_VOID => true,
+            0 => refund_if_empty && self.pool_yes == 0,
+            1 => refund_if_empty && self.pool_no == 0,
+            _ => false,
+        }
+    }
+
+    /// Every staked ticket owed a payout (every staked ticket, when stakes are refunded) has claimed,
+    /// or the unclaimed payouts have been swept.
+    pub fn all_claimed(&self) -> bool {
+        if self.unclaimed_swept {
+            return true;
+        }
+        let owed = if self.refunds_stakes() {
+            self.bettors_yes.saturating_add(self.bettors_no)
+        } else {
+            match self.winning_side {
+                0 => self.bettors_yes,
+                1 => self.bettors_no,
+                _ => 0,
+            }
+        };
+        self.claimed_count >= owed
+    }
+
+    /// A committed prize draw is still owed: claims (which close tickets) wait for it until
+    /// `prize_reveal_deadline`.
+    pub fn prize_draw_pending(&self, now: i64) -> bool {
+        let winners = match self.winning_side {
+            0 => self.bettors_yes,
+            1 => self.bettors_no,
+            _ => 0,
+        };
+        self.prize_seed_hash != [0; 32] && self.prize_winner == Pubkey::default() && winners > 0 && now < self.prize_reveal_deadline()
+    }
+
+    /// End of the prize draw's reveal window: `PRIZE_REVEAL_WINDOW_SECS` past the dispute deadline.
+    pub fn prize_reveal_deadline(&self) -> i64 {
+        self.resolved_at
+            .saturating_add(self.dispute_window_secs as i64)
+            .saturating_add(PRIZE_REVEAL_WINDOW_SECS)
+    }
 }
-// SIZE (without discriminator): previous 193 + 1 (frozen) = 194
-impl BetMarket { pub const SIZE: usize = 32 + 2 + 8 + 8 + 1 + 1 + 2 + 2 + 1 + 1 + 8 + 64 + 32 + 32; }
+
+/// Where `sweep_unclaimed` sends payouts left unclaimed past the claim deadline.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnclaimedPolicy {
+    #[default]
+    Treasury,
+    Authority,
+}
+
+/// What happens to the pool when the winning side has no bets.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoWinnerPolicy {
+    #[default]
+    Fees,     // the losing pool accrues as fees (split authority / host)
+    Refund,   // treated like a void result: every ticket reclaims its stake fee-free
+    Rollover, // the losing pool is carried into a later market of the authority via roll_over_pool
+}
+
+/// How `draw_prize_winner` weights winning tickets.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrizeWeighting {
+    #[default]
+    PerTicket, // one entry per winning ticket
+    ByStake,   // entries proportional to staked lamports
+}
+
+/// How a market's result was determined.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResolutionSource {
+    #[default]
+    Manual,  // authority / host decided by hand
+    AiOcr,   // delegated AI resolver (frame sampling + OCR)
+    Quorum,  // ResolutionVote K-of-N outcome (or void on tie/timeout)
+    Arbiter, // overturned by the dispute arbiter
+    Oracle,  // resolve_from_feed against the configured value feed
+    Timeout, // voided by void_stale_market after RESOLUTION_TIMEOUT_SECS unresolved
+}
+
+/// How a market is resolved.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarketKind {
+    #[default]
+    Standard, // resolve_market / ResolutionVote
+    Oracle,   // permissionless resolve_from_feed at resolve_ts
+}
+
+/// Numeric-outcome settings: YES wins if the feed value at `resolve_ts` is above `threshold`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OracleConfig {
+    pub feed: Pubkey,    // account in the `feed` module layout
+    pub resolve_ts: i64, // unix seconds; betting closes and resolution opens here
+    pub threshold: i64,  // same units as the feed value
+}
+
+/// Optional market settings supplied to `initialize_market`; `None` uses all defaults.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MarketOptions {
+    pub limits: BetLimits,
+    pub dispute: DisputeConfig,
+    pub quorum_threshold: u64, // pools at/above this must resolve via ResolutionVote (0 = never)
+    pub oracle: Option<OracleConfig>, // Some => MarketKind::Oracle
+    pub close_ts: i64,         // betting closes at this unix time; keepers freeze after it (0 = manual)
+    pub fixed_stake: u64,      // every ticket stakes exactly this once (0 = free amounts)
+    pub no_winner_policy: NoWinnerPolicy,
+    pub referral_bps: u16,     // share of a referred ticket's fee paid to the referrer (0 = no rewards)
+    pub claim_window_secs: u32, // claims expire this long after they open (0 = never, else >= MIN_CLAIM_WINDOW_SECS)
+    pub unclaimed_policy: UnclaimedPolicy,
+}
+
+/// Per-market betting limits. Zero disables a limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BetLimits {
+    pub min_bet: u64,
+    pub max_bet_per_ticket: u64,
+    pub max_pool: u64,
+}
+
+/// Dispute period after resolution. A zero window disables disputes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DisputeConfig {
+    pub window_secs: u32,
+    pub bond: u64,
+}
+
+#[account]
+pub struct DisputeBond {
+    pub market: Pubkey,
+    pub challenger: Pubkey,
+    pub amount: u64, // bond lamports held on top of rent
+    pub bump: u8,
+}
+impl DisputeBond { pub const SIZE: usize = 32 + 32 + 8 + 1; }
+
+#[account]
+pub struct ResolutionVote {
+    pub market: Pubkey,
+    pub resolvers: [Pubkey; MAX_RESOLVERS], // first resolver_count entries are valid
+    pub resolver_count: u8,
+    pub quorum: u8,                         // matching votes needed (K of N)
+    pub votes: [u8; MAX_RESOLVERS],         // side per resolver, VOTE_NONE if not cast
+    pub opened_at: i64,
+    pub timeout_secs: u32,                  // after opened_at + timeout the market can be voided
+    pub bump: u8,
+}
+impl ResolutionVote { pub const SIZE: usize = 32 + 32 * MAX_RESOLVERS + 1 + 1 + MAX_RESOLVERS + 8 + 4 + 1; }
 
 #[account]
 pub struct BetTicket {
@@ -354,13 +1514,47 @@ pub struct BetTicket {
     pub amount: u64,
     pub claimed: bool,
     pub bump: u8,
+    pub referrer: Pubkey, // earns market.referral_bps of this ticket's fees (default = no referrer)
+    pub entry_index: u32, // order of the first stake among its side's bettors (prize draw walk order)
+    pub market_serial: u64, // BetMarket::serial of the market it was opened in
+}
+impl BetTicket { pub const SIZE: usize = 32 + 32 + 1 + 8 + 1 + 1 + 32 + 4 + 8; }
+
+/// Program-owned singleton holding the platform's (host) fee share until `treasury_withdraw`.
+#[account]
+pub struct Treasury {
+    pub collected: u64, // lifetime host fees and swept payouts received (per market: BetMarket::treasury_paid)
+    pub withdrawn: u64, // lifetime treasury_withdraw payouts
+    pub bump: u8,
+    pub admin: Pubkey,  // signs treasury_withdraw and set_treasury_admin
+}
+impl Treasury { pub const SIZE: usize = 8 + 8 + 1 + 32; }
+
+#[account]
+pub struct FeeSchedule {
+    pub market: Pubkey,
+    pub recipients: [Pubkey; MAX_FEE_RECIPIENTS], // first recipient_count entries are valid
+    pub weights: [u16; MAX_FEE_RECIPIENTS],       // bps of the authority's fee share, summing to 10_000
+    pub recipient_count: u8,
+    pub remainder_index: u8,                      // recipient that takes the rounding remainder
+    pub bump: u8,
+}
+impl FeeSchedule { pub const SIZE: usize = 32 + 32 * MAX_FEE_RECIPIENTS + 2 * MAX_FEE_RECIPIENTS + 1 + 1 + 1; }
+
+#[account]
+pub struct ReferrerVault {
+    pub referrer: Pubkey,
+    pub accrued: u64,      // lamports held for the referrer until withdraw_referral_rewards
+    pub total_earned: u64, // lifetime rewards, for display
+    pub bump: u8,
 }
-impl BetTicket { pub const SIZE: usize = 32 + 32 + 1 + 8 + 1 + 1; }
+impl ReferrerVault { pub const SIZE: usize = 32 + 8 + 8 + 1; }
 
 // -------------------------------------------------------------------------------------------------
 // Accounts
 // -------------------------------------------------------------------------------------------------
 #[derive(Accounts)]
+#[instruction(cycle: u64)]
 pub struct InitializeMarket<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -375,7 +1569,78 @@ pub struct InitializeMarket<'info> {
         init,
         payer = authority,
         space = 8 + BetMarket::SIZE,
-        seeds = [b"market", authority.key().as_ref(), &authority_meta.next_cycle.to_le_bytes()],
+        seeds = [b"market", authority.key().as_ref(), &cycle.to_le_bytes()],
+        bump
+    )]
+    pub market: Account<'info, BetMarket>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u16)]
+pub struct CreateMarketTemplate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"authority_meta", authority.key().as_ref()],
+        bump = authority_meta.bump,
+        has_one = authority
+    )]
+    pub authority_meta: Account<'info, AuthorityMeta>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MarketTemplate::SIZE,
+        seeds = [b"template", authority_meta.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub template: Account<'info, MarketTemplate>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMarketTemplate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"authority_meta", authority.key().as_ref()],
+        bump = authority_meta.bump,
+        has_one = authority
+    )]
+    pub authority_meta: Account<'info, AuthorityMeta>,
+    #[account(
+        mut,
+        seeds = [b"template", authority_meta.key().as_ref(), &template.id.to_le_bytes()],
+        bump = template.bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub template: Account<'info, MarketTemplate>,
+}
+
+#[derive(Accounts)]
+#[instruction(cycle: u64)]
+pub struct InitializeMarketFromTemplate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"authority_meta", authority.key().as_ref()],
+        bump = authority_meta.bump,
+        has_one = authority
+    )]
+    pub authority_meta: Account<'info, AuthorityMeta>,
+    #[account(
+        seeds = [b"template", authority_meta.key().as_ref(), &template.id.to_le_bytes()],
+        bump = template.bump,
+        has_one = authority
+    )]
+    pub template: Account<'info, MarketTemplate>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BetMarket::SIZE,
+        seeds = [b"market", authority.key().as_ref(), &cycle.to_le_bytes()],
         bump
     )]
     pub market: Account<'info, BetMarket>,
@@ -396,6 +1661,7 @@ pub struct PlaceBet<'info> {
         mut,
         has_one = user,
         has_one = market,
+        constraint = ticket.market_serial == market.serial @ BetError::TicketMarketMismatch,
         seeds = [b"ticket", market.key().as_ref(), user.key().as_ref()],
         bump = ticket.bump
     )]
@@ -423,6 +1689,8 @@ pub struct CreateTicket<'info> {
         bump
     )]
     pub ticket: Account<'info, BetTicket>,
+    /// Required when the ticket names a referrer; checked against it in the handler.
+    pub referrer_vault: Option<Account<'info, ReferrerVault>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -438,10 +1706,159 @@ pub struct ResolveMarket<'info> {
     pub market: Account<'info, BetMarket>,
 }
 
+#[derive(Accounts)]
+pub struct InitResolutionVote<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"market", authority.key().as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+        has_one = authority,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ResolutionVote::SIZE,
+        seeds = [b"vote", market.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, ResolutionVote>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitResolutionVote<'info> {
+    pub resolver: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [b"vote", market.key().as_ref()],
+        bump = vote.bump
+    )]
+    pub vote: Account<'info, ResolutionVote>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireResolutionVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(
+        has_one = market,
+        seeds = [b"vote", market.key().as_ref()],
+        bump = vote.bump
+    )]
+    pub vote: Account<'info, ResolutionVote>,
+}
+
+#[derive(Accounts)]
+pub struct VoidStaleMarket<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveFromFeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+    /// CHECK: Unchecked; key compared to market.oracle_feed and data parsed via the `feed` layout.
+    pub feed: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ChallengeResolution<'info> {
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(
+        has_one = market,
+        constraint = ticket.market_serial == market.serial @ BetError::TicketMarketMismatch,
+        constraint = ticket.user == challenger.key() @ BetError::Unauthorized,
+        seeds = [b"ticket", market.key().as_ref(), challenger.key().as_ref()],
+        bump = ticket.bump
+    )]
+    pub ticket: Account<'info, BetTicket>,
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + DisputeBond::SIZE,
+        seeds = [b"bond", market.key().as_ref(), challenger.key().as_ref()],
+        bump
+    )]
+    pub bond: Account<'info, DisputeBond>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ArbitrateDispute<'info> {
+    pub arbiter: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireDispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+}
+
+#[derive(Accounts)]
+pub struct SettleDisputeBond<'info> {
+    #[account(mut)]
+    pub challenger: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(
+        mut,
+        close = challenger,
+        has_one = challenger,
+        has_one = market,
+        seeds = [b"bond", market.key().as_ref(), challenger.key().as_ref()],
+        bump = bond.bump
+    )]
+    pub bond: Account<'info, DisputeBond>,
+}
+
 #[derive(Accounts)]
 pub struct ClaimWinnings<'info> {
+    /// Ticket owner; receives the payout and ticket rent. Need not sign so keepers can crank claims.
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub user: SystemAccount<'info>,
     #[account(
         mut,
         seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
@@ -453,16 +1870,51 @@ pub struct ClaimWinnings<'info> {
         close = user,
         has_one = user,
         has_one = market,
+        constraint = ticket.market_serial == market.serial @ BetError::TicketMarketMismatch,
         seeds = [b"ticket", market.key().as_ref(), user.key().as_ref()],
         bump = ticket.bump
     )]
     pub ticket: Account<'info, BetTicket>,
+    /// The ticket referrer's vault; required when the claim pays a referral share.
+    #[account(mut, constraint = referrer_vault.referrer == ticket.referrer @ BetError::ReferrerVaultMissing)]
+    pub referrer_vault: Option<Account<'info, ReferrerVault>>,
+}
+
+#[derive(Accounts)]
+pub struct InitReferrerVault<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferrerVault::SIZE,
+        seeds = [b"referrer_vault", referrer.key().as_ref()],
+        bump
+    )]
+    pub referrer_vault: Account<'info, ReferrerVault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawReferralRewards<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    #[account(
+        mut,
+        has_one = referrer,
+        seeds = [b"referrer_vault", referrer.key().as_ref()],
+        bump = referrer_vault.bump,
+    )]
+    pub referrer_vault: Account<'info, ReferrerVault>,
 }
 
 #[derive(Accounts)]
 pub struct WithdrawFees<'info> {
+    /// The authority, or anyone once every payout has been claimed.
+    pub caller: Signer<'info>,
+    /// Receives the authority fee share (without a fee schedule); need not sign.
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub authority: SystemAccount<'info>,
     #[account(
         mut,
         has_one = authority,
@@ -470,15 +1922,86 @@ pub struct WithdrawFees<'info> {
         bump = market.bump,
     )]
     pub market: Account<'info, BetMarket>,
-    /// CHECK: Unchecked; validated by comparing pubkey to HOST_PUBKEY constant inside handler.
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Required iff the market has a fee schedule; its recipients follow as remaining accounts.
+    #[account(has_one = market @ BetError::FeeScheduleMismatch)]
+    pub fee_schedule: Option<Account<'info, FeeSchedule>>,
+}
+
+#[derive(Accounts)]
+pub struct InitTreasury<'info> {
     #[account(mut)]
-    pub host: UncheckedAccount<'info>,
+    pub payer: Signer<'info>,
+    #[account(init, payer = payer, space = 8 + Treasury::SIZE, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CloseMarket<'info> {
+pub struct SetTreasuryAdmin<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump, has_one = admin @ BetError::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+#[derive(Accounts)]
+pub struct TreasuryWithdraw<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump, has_one = admin @ BetError::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+    /// CHECK: Any account chosen by the admin; only receives lamports.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepUnclaimed<'info> {
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"market", authority.key().as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+    /// Receives the sweep under UnclaimedPolicy::Authority; need not sign.
+    #[account(mut)]
+    pub authority: SystemAccount<'info>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeSchedule<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", authority.key().as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+        has_one = authority,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FeeSchedule::SIZE,
+        seeds = [b"fee_schedule", market.key().as_ref()],
+        bump
+    )]
+    pub fee_schedule: Account<'info, FeeSchedule>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    /// The authority, or anyone once every payout has been claimed (tipped from the rent).
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    /// Receives the market rent; need not sign.
+    #[account(mut)]
+    pub authority: SystemAccount<'info>,
     #[account(
         mut,
         has_one = authority,
@@ -487,15 +2010,18 @@ pub struct CloseMarket<'info> {
         bump = market.bump,
     )]
     pub market: Account<'info, BetMarket>,
-    /// CHECK: Unchecked; validated by comparing pubkey to HOST_PUBKEY constant inside handler.
-    #[account(mut)]
-    pub host: UncheckedAccount<'info>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    /// Required iff the market has a fee schedule; closed along with the market.
+    #[account(mut, close = authority, has_one = market @ BetError::FeeScheduleMismatch)]
+    pub fee_schedule: Option<Account<'info, FeeSchedule>>,
 }
 
 #[derive(Accounts)]
 pub struct CloseTicket<'info> {
+    /// Ticket owner; receives the ticket rent. Need not sign so keepers can clean up losing tickets.
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub user: SystemAccount<'info>,
     #[account(
         mut,
         seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
@@ -507,12 +2033,84 @@ pub struct CloseTicket<'info> {
         close = user,
         has_one = user,
         has_one = market,
+        constraint = ticket.market_serial == market.serial @ BetError::TicketMarketMismatch,
         seeds = [b"ticket", market.key().as_ref(), user.key().as_ref()],
         bump = ticket.bump
     )]
     pub ticket: Account<'info, BetTicket>,
 }
 
+#[derive(Accounts)]
+pub struct CloseOrphanTicket<'info> {
+    /// Ticket owner; receives the ticket rent. Need not sign so keepers can clean up.
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+    /// CHECK: The ticket's market address; must no longer hold an account (checked in the handler).
+    pub market: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = user,
+        has_one = user,
+        has_one = market,
+        seeds = [b"ticket", market.key().as_ref(), user.key().as_ref()],
+        bump = ticket.bump
+    )]
+    pub ticket: Account<'info, BetTicket>,
+}
+
+#[derive(Accounts)]
+pub struct RollOverPool<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", authority.key().as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+        has_one = authority,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(
+        mut,
+        seeds = [b"market", authority.key().as_ref(), &destination.cycle.to_le_bytes()],
+        bump = destination.bump,
+        has_one = authority,
+    )]
+    pub destination: Account<'info, BetMarket>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireRollover<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+    #[account(mut, seeds = [b"treasury"], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+#[derive(Accounts)]
+pub struct CommitPrizeSeed<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", authority.key().as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+        has_one = authority,
+    )]
+    pub market: Account<'info, BetMarket>,
+}
+
+#[derive(Accounts)]
+pub struct DrawPrizeWinner<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", market.authority.as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, BetMarket>,
+}
+
 #[derive(Accounts)]
 pub struct FreezeMarket<'info> {
     #[account(mut)]
@@ -524,9 +2122,22 @@ pub struct FreezeMarket<'info> {
         has_one = authority,
     )]
     pub market: Account<'info, BetMarket>,
+    /// CHECK: SlotHashes sysvar (address-checked); only the newest entry is read, by freeze_market.
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
 }
 
-// Phase 1 excludes resolve/claim/withdraw; will be added in next phase.
+#[derive(Accounts)]
+pub struct UpdateMarketMetadata<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", authority.key().as_ref(), &market.cycle.to_le_bytes()],
+        bump = market.bump,
+        has_one = authority,
+    )]
+    pub market: Account<'info, BetMarket>,
+}
 
 // -------------------------------------------------------------------------------------------------
 // Errors
@@ -553,6 +2164,73 @@ pub enum BetError {
     #[msg("Market is frozen")] MarketFrozen,
     #[msg("Market not frozen")] MarketNotFrozen,
     #[msg("Market already frozen")] MarketAlreadyFrozen,
+    #[msg("Invalid bet limits")] InvalidBetLimits,
+    #[msg("Label or title contains invalid characters")] InvalidLabelText,
+    #[msg("Market already has bets")] MarketHasBets,
+    #[msg("Market reopen limit reached")] ReopenLimitReached,
+    #[msg("Invalid dispute config")] InvalidDisputeConfig,
+    #[msg("Disputes are disabled for this market")] DisputesDisabled,
+    #[msg("Dispute window closed")] DisputeWindowClosed,
+    #[msg("Dispute window still open")] DisputeWindowOpen,
+    #[msg("Resolution is under dispute")] DisputePending,
+    #[msg("Dispute already settled")] DisputeAlreadySettled,
+    #[msg("Resolution not challenged")] NotChallenged,
+    #[msg("Dispute not settled")] DisputeUnsettled,
+    #[msg("Pool above quorum threshold; resolve via ResolutionVote")] QuorumRequired,
+    #[msg("Invalid resolver set or quorum")] InvalidQuorum,
+    #[msg("Resolver already voted")] AlreadyVoted,
+    #[msg("Resolution vote expired")] VoteExpired,
+    #[msg("Resolution vote still open")] VoteStillOpen,
+    #[msg("Confidence must be at most 10000 bps")] InvalidConfidence,
+    #[msg("Resolution source not allowed here")] InvalidResolutionSource,
+    #[msg("Invalid oracle config")] InvalidOracleConfig,
+    #[msg("Oracle markets resolve via resolve_from_feed")] OracleMarket,
+    #[msg("Not an oracle market")] NotOracleMarket,
+    #[msg("Feed account mismatch")] FeedMismatch,
+    #[msg("Invalid feed account data")] InvalidFeed,
+    #[msg("Feed has no observation for resolve_ts yet")] FeedStale,
+    #[msg("Too early to resolve")] ResolveTooEarly,
+    #[msg("Betting closed")] BettingClosed,
+    #[msg("Close time must be in the future")] InvalidCloseTime,
+    #[msg("Bet below market minimum")] BetBelowMinimum,
+    #[msg("Bet exceeds per-ticket maximum")] BetAboveMaximum,
+    #[msg("Bet exceeds market pool cap")] PoolCapExceeded,
+    #[msg("Authority meta already uses the current layout")] AlreadyMigrated,
+    #[msg("Bet must equal the market's fixed stake")] FixedStakeMismatch,
+    #[msg("Ticket already holds its fixed stake")] AlreadyStaked,
+    #[msg("Prize seed already committed")] PrizeAlreadyCommitted,
+    #[msg("No prize seed committed")] NoPrizeCommitted,
+    #[msg("Revealed seed does not match the commitment")] PrizeSeedMismatch,
+    #[msg("Prize winner already drawn")] PrizeAlreadyDrawn,
+    #[msg("No winning tickets to draw from")] NoPrizeEntrants,
+    #[msg("Winning tickets missing, duplicated or unsorted")] PrizeEntrantsInvalid,
+    #[msg("Claims wait for the prize draw")] PrizeDrawPending,
+    #[msg("Roll the pool over (or let it expire) before closing")] RolloverPending,
+    #[msg("Rollover target must be a later market of the same authority")] InvalidRolloverTarget,
+    #[msg("Referrer cannot be the bettor or the market authority")] InvalidReferrer,
+    #[msg("Referrer vault missing or not the ticket's referrer")] ReferrerVaultMissing,
+    #[msg("Fee schedule needs 1-8 distinct recipients with weights summing to 10000 bps")] InvalidFeeSchedule,
+    #[msg("Fee schedule or recipient accounts do not match the market")] FeeScheduleMismatch,
+    #[msg("Only the authority can do this before every payout is claimed")] ClaimsOutstanding,
+    #[msg("Claim deadline has passed")] ClaimExpired,
+    #[msg("Claim deadline has not passed")] ClaimWindowOpen,
+    #[msg("Unclaimed payouts already swept")] AlreadySwept,
+    #[msg("Market account still exists; use close_ticket")] MarketStillOpen,
+    #[msg("Claim window must be zero or at least MIN_CLAIM_WINDOW_SECS")] InvalidClaimWindow,
+    #[msg("Arbitration deadline has passed")] ArbitrationExpired,
+    #[msg("Arbitration deadline has not passed")] ArbitrationOpen,
+    #[msg("Dispute bonds must be settled before the market closes")] BondsOutstanding,
+    #[msg("Rollover deadline has not passed")] RolloverWindowOpen,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAuthorityMeta<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Legacy-layout AuthorityMeta; owner, discriminator, size and authority are checked in the handler.
+    #[account(mut, seeds = [b"authority_meta", authority.key().as_ref()], bump)]
+    pub authority_meta: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -570,13 +2248,317 @@ pub struct InitAuthorityMeta<'info> {
     pub system_program: Program<'info, System>,
 }
 
-// Utility to write into fixed-size arrays (null padding)
-fn write_fixed<const N: usize>(dst: &mut [u8; N], src: &[u8]) {
-    for i in 0..N { dst[i] = 0; }
-    if !src.is_empty() {
-        let len = core::cmp::min(N, src.len());
-        dst[..len].copy_from_slice(&src[..len]);
+// Record the winning side. If it has zero bets, the market's NoWinnerPolicy applies to the losing
+// pool: `Fees` converts it to fees, `Rollover` sets it aside for roll_over_pool, `Refund` leaves it
+// for fee-free refunds. SIDE_VOID leaves both pools untouched for fee-free refunds. A rolled-in
+// bonus only pays winners; without any it moves on again via roll_over_pool.
+fn apply_resolution(market: &mut Account<BetMarket>, winning_side: u8) -> Result<()> {
+    market.winning_side = winning_side;
+    let (winning_pool, losing_pool) = match winning_side { 0 => (market.pool_yes, market.pool_no), 1 => (market.pool_no, market.pool_yes), _ => (0,0) };
+    if winning_pool == 0 && losing_pool > 0 && market.no_winner_policy != NoWinnerPolicy::Refund {
+        // The losing_pool lamports stay inside the market escrow account: just move them to the policy's bucket.
+        if market.no_winner_policy == NoWinnerPolicy::Fees {
+            market.fees_accrued = market.fees_accrued.checked_add(losing_pool).ok_or(BetError::MathOverflow)?;
+        } else {
+            market.rollover_pending = market.rollover_pending.checked_add(losing_pool).ok_or(BetError::MathOverflow)?;
+        }
+        // Zero out the losing pool to reflect accounting invariant: pool sums = remaining claimable + set aside.
+        if winning_side == 0 { market.pool_no = 0; } else { market.pool_yes = 0; }
+    }
+    if winning_pool == 0 && market.bonus_pool > 0 {
+        market.rollover_pending = market.rollover_pending.checked_add(market.bonus_pool).ok_or(BetError::MathOverflow)?;
+    }
+    // Final once the dispute window elapses; arbitration moves the deadline to the ruling.
+    let final_at = dispute_deadline(market)?;
+    set_claim_deadline(market, final_at)?;
+    let no_winner = winning_side <= 1 && winning_pool == 0;
+    emit!(MarketResolvedEvent {
+        market: market.key(),
+        authority: market.authority,
+        winning_side,
+        pool_yes: market.pool_yes,
+        pool_no: market.pool_no,
+        no_winner,
+        fees_accrued: market.fees_accrued,
+        evidence_hash: market.evidence_hash,
+        confidence_bps: market.confidence_bps,
+        source: market.resolution_source,
+    });
+    Ok(())
+}
+
+// Undo apply_resolution's no-winner accrual. Only valid while claims, fee withdrawals and
+// rollovers are blocked (i.e. during a dispute), when fees_accrued / rollover_pending still hold
+// exactly what apply_resolution set aside.
+fn revert_resolution(market: &mut Account<BetMarket>) -> Result<()> {
+    let winning_pool = match market.winning_side { 0 => market.pool_yes, 1 => market.pool_no, _ => 0 };
+    if winning_pool == 0 {
+        let restored = match market.no_winner_policy {
+            NoWinnerPolicy::Fees => core::mem::take(&mut market.fees_accrued),
+            NoWinnerPolicy::Rollover => market.rollover_pending.checked_sub(market.bonus_pool).ok_or(BetError::MathOverflow)?,
+            NoWinnerPolicy::Refund => 0,
+        };
+        if restored > 0 {
+            if market.winning_side == 0 { market.pool_no = restored; } else { market.pool_yes = restored; }
+        }
+        market.rollover_pending = 0;
+    }
+    Ok(())
+}
+
+// The authority's fee share goes to the authority, or across the fee schedule's recipients, which
+// must be passed as writable `remaining_accounts` in schedule order.
+// Returns the part of the share no recipient could take, which the caller pays into the treasury.
+// A share that would leave its recipient below rent exemption (an unfunded wallet) would fail the
+// whole transaction, forever blocking withdraw_fees and close_market: it rolls into the remainder
+// recipient's share instead, and if even that recipient cannot take the lot it is returned.
+fn pay_authority_share(authority: &AccountInfo, schedule: Option<&FeeSchedule>, recipients: &[AccountInfo], amount: u64) -> Result<u64> {
+    let Some(schedule) = schedule else {
+        **authority.try_borrow_mut_lamports()? = authority.lamports().checked_add(amount).ok_or(BetError::MathOverflow)?;
+        return Ok(0);
+    };
+    let n = schedule.recipient_count as usize;
+    require!(recipients.len() == n, BetError::FeeScheduleMismatch);
+    for (info, expected) in recipients.iter().zip(&schedule.recipients[..n]) {
+        require!(info.key() == *expected && info.is_writable, BetError::FeeScheduleMismatch);
+    }
+    let rent = Rent::get()?;
+    let can_take = |info: &AccountInfo, share: u64| -> Result<bool> {
+        let balance = info.lamports().checked_add(share).ok_or(BetError::MathOverflow)?;
+        Ok(rent.is_exempt(balance, info.data_len()))
+    };
+    let remainder_index = schedule.remainder_index as usize;
+    let shares = math::split_by_weights(amount, &schedule.weights, remainder_index).ok_or(BetError::MathOverflow)?;
+    let mut rolled = shares[remainder_index];
+    for (i, (info, share)) in recipients.iter().zip(shares).enumerate() {
+        if i == remainder_index || share == 0 {
+            continue;
+        }
+        if can_take(info, share)? {
+            **info.try_borrow_mut_lamports()? = info.lamports().checked_add(share).ok_or(BetError::MathOverflow)?;
+        } else {
+            rolled = rolled.checked_add(share).ok_or(BetError::MathOverflow)?;
+        }
+    }
+    let remainder = &recipients[remainder_index];
+    if rolled == 0 || !can_take(remainder, rolled)? {
+        return Ok(rolled);
+    }
+    **remainder.try_borrow_mut_lamports()? = remainder.lamports().checked_add(rolled).ok_or(BetError::MathOverflow)?;
+    Ok(0)
+}
+
+fn vote_deadline(vote: &ResolutionVote) -> Result<i64> {
+    Ok(vote.opened_at.checked_add(vote.timeout_secs as i64).ok_or(BetError::MathOverflow)?)
+}
+
+// Claims expire `claim_window_secs` after they open: at `final_at`, or once the reveal window of a
+// committed prize draw has passed (claims are held until then), so the full window is always usable.
+fn set_claim_deadline(market: &mut BetMarket, final_at: i64) -> Result<()> {
+    let opens = if market.prize_seed_hash != [0; 32] { final_at.max(market.prize_reveal_deadline()) } else { final_at };
+    market.claim_deadline = match market.claim_window_secs {
+        0 => 0,
+        secs => opens.checked_add(secs as i64).ok_or(BetError::MathOverflow)?,
+    };
+    Ok(())
+}
+
+fn dispute_deadline(market: &BetMarket) -> Result<i64> {
+    Ok(market.resolved_at.checked_add(market.dispute_window_secs as i64).ok_or(BetError::MathOverflow)?)
+}
+
+// A resolution is final once its dispute window has elapsed unchallenged or the arbiter has ruled.
+fn require_final(market: &BetMarket) -> Result<()> {
+    match market.dispute_state {
+        DISPUTE_CHALLENGED => err!(BetError::DisputePending),
+        DISPUTE_UPHELD | DISPUTE_OVERTURNED | DISPUTE_EXPIRED => Ok(()),
+        _ => {
+            if market.dispute_window_secs > 0 {
+                require!(Clock::get()?.unix_timestamp >= dispute_deadline(market)?, BetError::DisputeWindowOpen);
+            }
+            Ok(())
+        }
+    }
+}
+
+// Record market `cycle` in the authority's meta and return the new market's serial. Any unused id
+// is accepted (the market PDA's `init` rejects open ones); `next_cycle` only suggests the next id.
+fn take_cycle(meta: &mut AuthorityMeta, cycle: u64) -> Result<u64> {
+    let next = cycle.checked_add(1).ok_or(BetError::MathOverflow)?;
+    meta.next_cycle = meta.next_cycle.max(next);
+    meta.markets_opened = meta.markets_opened.checked_add(1).ok_or(BetError::MathOverflow)?;
+    Ok(meta.markets_opened)
+}
+
+// Reset every field of a freshly created market (shared by `initialize_market` and
+// `initialize_market_from_template`). Text is written separately by the caller.
+fn init_market_state(
+    market: &mut BetMarket,
+    authority: Pubkey,
+    cycle: u64,
+    bump: u8,
+    fee_bps: u16,
+    options: MarketOptions,
+) -> Result<()> {
+    market.authority = authority;
+    market.cycle = cycle;
+    market.pool_yes = 0;
+    market.pool_no = 0;
+    market.resolved = false;
+    market.frozen = false;
+    check_fee(fee_bps)?;
+    market.fee_bps = fee_bps;
+    market.host_fee_bps = HOST_FEE_BPS_DEFAULT;
+    market.bump = bump;
+    market.winning_side = 255; // sentinel for not set
+    market.fees_accrued = 0;
+    market.bettors_yes = 0;
+    market.bettors_no = 0;
+    market.claimed_total = 0;
+    market.claimed_count = 0;
+    let MarketOptions {
+        limits,
+        dispute,
+        quorum_threshold,
+        oracle,
+        close_ts,
+        fixed_stake,
+        no_winner_policy,
+        referral_bps,
+        claim_window_secs,
+        unclaimed_policy,
+    } = options;
+    check_limits(&limits)?;
+    require!(referral_bps as u64 <= math::BPS_DENOMINATOR, BetError::InvalidFee);
+    market.referral_bps = referral_bps;
+    market.has_fee_schedule = false;
+    market.treasury_paid = 0;
+    require!(
+        claim_window_secs == 0 || claim_window_secs >= MIN_CLAIM_WINDOW_SECS,
+        BetError::InvalidClaimWindow
+    );
+    market.claim_window_secs = claim_window_secs;
+    market.claim_deadline = 0;
+    market.unclaimed_policy = unclaimed_policy;
+    market.unclaimed_swept = false;
+    // Per-ticket bounds are meaningless when every ticket stakes the same amount.
+    require!(
+        fixed_stake == 0 || (limits.min_bet == 0 && limits.max_bet_per_ticket == 0),
+        BetError::InvalidBetLimits
+    );
+    market.fixed_stake = fixed_stake;
+    market.no_winner_policy = no_winner_policy;
+    market.bonus_pool = 0;
+    market.rollover_pending = 0;
+    market.prize_seed_hash = [0; 32];
+    market.prize_weighting = PrizeWeighting::PerTicket;
+    market.prize_winner = Pubkey::default();
+    market.prize_entropy = [0; 32];
+    market.prize_cursor = Pubkey::default();
+    market.prize_counted = 0;
+    market.prize_staked = 0;
+    market.prize_candidate = Pubkey::default();
+    market.min_bet = limits.min_bet;
+    market.max_bet_per_ticket = limits.max_bet_per_ticket;
+    market.max_pool = limits.max_pool;
+    market.reopen_count = 0;
+    market.frozen_at = 0;
+    require!(dispute.window_secs == 0 || dispute.bond > 0, BetError::InvalidDisputeConfig);
+    market.dispute_window_secs = dispute.window_secs;
+    market.dispute_bond = dispute.bond;
+    market.dispute_state = DISPUTE_NONE;
+    market.challenge_count = 0;
+    market.open_bonds = 0;
+    market.resolved_at = 0;
+    market.quorum_threshold = quorum_threshold;
+    market.evidence_hash = [0; 32];
+    market.confidence_bps = 0;
+    market.resolution_source = ResolutionSource::Manual;
+    let now = Clock::get()?.unix_timestamp;
+    require!(close_ts == 0 || close_ts > now, BetError::InvalidCloseTime);
+    market.close_ts = close_ts;
+    match oracle {
+        Some(cfg) => {
+            require!(cfg.feed != Pubkey::default(), BetError::InvalidOracleConfig);
+            require!(cfg.resolve_ts > now, BetError::InvalidOracleConfig);
+            market.kind = MarketKind::Oracle;
+            market.oracle_feed = cfg.feed;
+            market.resolve_ts = cfg.resolve_ts;
+            market.oracle_threshold = cfg.threshold;
+        }
+        None => {
+            market.kind = MarketKind::Standard;
+            market.oracle_feed = Pubkey::default();
+            market.resolve_ts = 0;
+            market.oracle_threshold = 0;
+        }
+    }
+    Ok(())
+}
+
+// Credit the host fee share (already debited from the market escrow) to the treasury.
+fn credit_treasury(market: &mut Account<BetMarket>, treasury: &mut Account<Treasury>, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
     }
+    **treasury.to_account_info().try_borrow_mut_lamports()? =
+        treasury.to_account_info().lamports().checked_add(amount).ok_or(BetError::MathOverflow)?;
+    treasury.collected = treasury.collected.checked_add(amount).ok_or(BetError::MathOverflow)?;
+    market.treasury_paid = market.treasury_paid.checked_add(amount).ok_or(BetError::MathOverflow)?;
+    emit!(TreasuryDepositEvent { market: market.key(), amount });
+    Ok(())
+}
+
+// Fee withdrawal and close: the authority may always send them, anyone else once the market is
+// resolved and every payout has been claimed, so rent and fees cannot be stranded.
+fn require_crank_allowed(market: &BetMarket, caller: &Pubkey) -> Result<()> {
+    if *caller != market.authority {
+        require!(market.resolved, BetError::MarketNotResolved);
+        require!(market.all_claimed(), BetError::ClaimsOutstanding);
+    }
+    Ok(())
+}
+
+fn check_fee(fee_bps: u16) -> Result<()> {
+    require!(fee_bps <= 10_000, BetError::InvalidFee);
+    require!(fee_bps as u32 + HOST_FEE_BPS_DEFAULT as u32 <= 10_000, BetError::InvalidFee);
+    Ok(())
+}
+
+fn check_limits(limits: &BetLimits) -> Result<()> {
+    require!(
+        limits.max_bet_per_ticket == 0 || limits.min_bet <= limits.max_bet_per_ticket,
+        BetError::InvalidBetLimits
+    );
+    require!(limits.max_pool == 0 || limits.min_bet <= limits.max_pool, BetError::InvalidBetLimits);
+    Ok(())
+}
+
+// Validate and store title + labels. Strings arrive as Borsh `String`, which is already UTF-8
+// checked on deserialization; NUL is rejected because it is the padding byte clients trim on.
+fn write_market_text(market: &mut BetMarket, title: &str, label_yes: &str, label_no: &str) -> Result<()> {
+    check_market_text(title, label_yes, label_no)?;
+    write_fixed(&mut market.title, title);
+    write_fixed(&mut market.label_yes, label_yes);
+    write_fixed(&mut market.label_no, label_no);
+    Ok(())
+}
+
+fn check_market_text(title: &str, label_yes: &str, label_no: &str) -> Result<()> {
+    for (text, max) in [(title, TITLE_MAX_LEN), (label_yes, LABEL_MAX_LEN), (label_no, LABEL_MAX_LEN)] {
+        require!(text.len() <= max, BetError::LabelTooLong);
+        require!(!text.contains('\0'), BetError::InvalidLabelText);
+    }
+    Ok(())
+}
+
+// Utility to write into fixed-size arrays (null padding). Truncates on a char boundary so the
+// stored bytes always remain valid UTF-8.
+fn write_fixed<const N: usize>(dst: &mut [u8; N], src: &str) {
+    dst.fill(0);
+    let mut len = core::cmp::min(N, src.len());
+    while !src.is_char_boundary(len) { len -= 1; }
+    dst[..len].copy_from_slice(&src.as_bytes()[..len]);
 }
 
 // (Removed experimental unified bet instruction using init_if_needed; handled client-side instead.)
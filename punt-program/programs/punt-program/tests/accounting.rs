@@ -0,0 +1,332 @@
+//! Escrow accounting invariants, driven through the real program.
+//!
+//! Every case runs a market end to end on a native `solana-program-test` bank: up to
+//! MAX_BETTORS tickets (some referred), an optional fee schedule, any `NoWinnerPolicy`, a yes, no
+//! or void result, claims (with the rest swept after the claim deadline), a rollover into a later
+//! market, fee withdrawal and close, cranked by the authority or a third party. Lamports summed
+//! over every account involved are conserved, the escrow never drops below what it owes, and
+//! `close_market` always succeeds and leaves nothing behind.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use common::*;
+use proptest::prelude::*;
+use punt_program::{
+    accounts, instruction, math, BetError, BetMarket, MarketOptions, NoWinnerPolicy, ReferrerVault,
+    UnclaimedPolicy, CRANK_TIP_LAMPORTS, HOST_FEE_BPS_DEFAULT, MAX_FEE_RECIPIENTS, MIN_CLAIM_WINDOW_SECS, SIDE_VOID,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const MAX_BETTORS: usize = 40;
+
+#[derive(Debug, Clone)]
+struct Bet {
+    side: u8,
+    amount: u64,
+    referred: bool,
+    claims: bool, // claims before the deadline (only meaningful with a claim window)
+}
+
+#[derive(Debug, Clone)]
+struct Scenario {
+    fee_bps: u16,
+    referral_bps: u16,
+    no_winner_policy: NoWinnerPolicy,
+    weights: Vec<u16>, // fee schedule weights (empty = no schedule)
+    bets: Vec<Bet>,
+    outcome: u8,
+    claim_window: bool,
+    unclaimed_policy: UnclaimedPolicy,
+    crank: bool, // a third party withdraws fees, sweeps and closes
+}
+
+async fn run(s: Scenario) -> std::result::Result<(), String> {
+    let bettors: Vec<Keypair> = s.bets.iter().map(|_| Keypair::new()).collect();
+    let recipients: Vec<Pubkey> = s.weights.iter().map(|_| Pubkey::new_unique()).collect();
+    let rollover_bettor = Keypair::new();
+    let wallets: Vec<Pubkey> = bettors.iter().chain([&rollover_bettor]).map(|k| k.pubkey()).collect();
+    let mut h = Harness::start(&wallets).await;
+    // Recipients start unfunded: fee shares below the rent-exempt minimum must not block the close.
+    h.tracked.extend_from_slice(&recipients);
+
+    let options = MarketOptions {
+        no_winner_policy: s.no_winner_policy,
+        referral_bps: s.referral_bps,
+        claim_window_secs: if s.claim_window { MIN_CLAIM_WINDOW_SECS } else { 0 },
+        unclaimed_policy: s.unclaimed_policy,
+        ..Default::default()
+    };
+    let market = h.open_market(0, s.fee_bps, options).await;
+    if !recipients.is_empty() {
+        let authority = h.authority.insecure_clone();
+        let fee_schedule = pda(&[b"fee_schedule", market.as_ref()]);
+        let ix = build(
+            accounts::SetFeeSchedule { authority: authority.pubkey(), market, fee_schedule, system_program: system_program::ID },
+            instruction::SetFeeSchedule { recipients: recipients.clone(), weights: s.weights.clone(), remainder_index: 0 },
+        );
+        h.send(ix, &[&authority]).await.map_err(|e| format!("set_fee_schedule: {e}"))?;
+        h.tracked.push(fee_schedule);
+    }
+    let start = h.total().await;
+    let before: Vec<u64> = {
+        let mut v = Vec::new();
+        for b in &bettors {
+            v.push(h.lamports(b.pubkey()).await);
+        }
+        v
+    };
+    let cranker_before = h.lamports(h.cranker.pubkey()).await;
+
+    for (bet, user) in s.bets.iter().zip(&bettors) {
+        h.bet(market, user, bet.side, bet.amount, bet.referred).await;
+    }
+    h.freeze_and_resolve(market, s.outcome).await;
+    h.check_escrow(market).await?;
+
+    let state: BetMarket = h.load(market).await;
+    let refund = state.refunds_stakes();
+    let owed: Vec<bool> = s.bets.iter().map(|b| refund || b.side == state.winning_side).collect();
+    let cranker = h.cranker.insecure_clone();
+    if owed.iter().any(|o| *o) {
+        // Third parties may not crank fees or close while payouts are outstanding.
+        let ix = h.close_market_ix(&cranker.pubkey(), market, &recipients);
+        expect_error(h.rejection(ix, &[&cranker]).await, code(BetError::ClaimsOutstanding), "crank close")?;
+        let ix = h.withdraw_fees_ix(&cranker.pubkey(), market, &recipients);
+        expect_error(h.rejection(ix, &[&cranker]).await, code(BetError::ClaimsOutstanding), "crank withdraw")?;
+    }
+
+    let mut unclaimed = Vec::new();
+    for ((bet, user), owed) in s.bets.iter().zip(&bettors).zip(&owed) {
+        if *owed && (!s.claim_window || bet.claims) {
+            h.claim(market, &user.pubkey()).await.map_err(|e| format!("claim: {e}"))?;
+            h.check_escrow(market).await?;
+        } else if *owed {
+            unclaimed.push(user.pubkey());
+        } else {
+            h.close_ticket(market, &user.pubkey()).await.map_err(|e| format!("close_ticket: {e}"))?;
+        }
+    }
+    if !unclaimed.is_empty() {
+        let state: BetMarket = h.load(market).await;
+        h.warp_to(state.claim_deadline).await;
+        let caller = h.caller(s.crank);
+        let ix = build(
+            accounts::SweepUnclaimed { caller: caller.pubkey(), market, authority: h.authority.pubkey(), treasury: h.treasury },
+            instruction::SweepUnclaimed {},
+        );
+        h.send(ix, &[&caller]).await.map_err(|e| format!("sweep_unclaimed: {e}"))?;
+        h.check_escrow(market).await?;
+        for user in &unclaimed {
+            h.close_ticket(market, user).await.map_err(|e| format!("close_ticket after sweep: {e}"))?;
+        }
+    }
+
+    let state: BetMarket = h.load(market).await;
+    if state.rollover_pending > 0 {
+        // Carry the pool into the authority's next market, whose single winner collects it.
+        let destination = h.open_market(1, s.fee_bps, MarketOptions::default()).await;
+        let authority = h.authority.insecure_clone();
+        let ix = build(
+            accounts::RollOverPool { authority: authority.pubkey(), market, destination },
+            instruction::RollOverPool {},
+        );
+        h.send(ix, &[&authority]).await.map_err(|e| format!("roll_over_pool: {e}"))?;
+        h.bet(destination, &rollover_bettor, 0, 1_000_000_000, false).await;
+        h.freeze_and_resolve(destination, 0).await;
+        h.claim(destination, &rollover_bettor.pubkey()).await.map_err(|e| format!("rollover claim: {e}"))?;
+        h.settle(destination, &[], false).await?;
+        if h.exists(destination).await {
+            return Err("rollover destination still open".into());
+        }
+    }
+
+    let authority_share = h.settle(market, &recipients, s.crank).await?;
+    if h.exists(market).await || h.exists(pda(&[b"fee_schedule", market.as_ref()])).await {
+        return Err("market or fee schedule still open after close".into());
+    }
+    for user in &bettors {
+        if h.exists(ticket_pda(&market, &user.pubkey())).await {
+            return Err("ticket left open".into());
+        }
+    }
+
+    let vault: ReferrerVault = h.load(h.referrer_vault).await;
+    if vault.accrued > 0 {
+        let referrer = h.referrer.insecure_clone();
+        let ix = build(
+            accounts::WithdrawReferralRewards { referrer: referrer.pubkey(), referrer_vault: h.referrer_vault },
+            instruction::WithdrawReferralRewards {},
+        );
+        h.send(ix, &[&referrer]).await.map_err(|e| format!("withdraw_referral_rewards: {e}"))?;
+    }
+
+    let end = h.total().await;
+    if end != start {
+        return Err(format!("lamports not conserved: {start} -> {end}"));
+    }
+    for ((bet, user), before) in s.bets.iter().zip(&bettors).zip(&before) {
+        let after = h.lamports(user.pubkey()).await;
+        let claimed_refund = refund && (!s.claim_window || bet.claims);
+        let expected = if claimed_refund {
+            Some(*before)
+        } else if refund || bet.side != state.winning_side || (s.claim_window && !bet.claims) {
+            Some(before - bet.amount)
+        } else {
+            None
+        };
+        if let Some(expected) = expected.filter(|e| *e != after) {
+            return Err(format!("bettor ended with {after}, expected {expected}"));
+        }
+    }
+    let tip = h.lamports(h.cranker.pubkey()).await - cranker_before;
+    if tip != if s.crank { CRANK_TIP_LAMPORTS } else { 0 } {
+        return Err(format!("cranker received {tip}"));
+    }
+    if !recipients.is_empty() {
+        // A share that would leave its recipient below rent exemption goes to the remainder
+        // recipient (index 0 here), or to the treasury if that one cannot take it either.
+        let rent: Rent = h.ctx.banks_client.get_sysvar().await.unwrap();
+        let mut weights = [0; MAX_FEE_RECIPIENTS];
+        weights[..s.weights.len()].copy_from_slice(&s.weights);
+        let shares = math::split_by_weights(authority_share, &weights, 0).unwrap();
+        let mut expected = vec![0; recipients.len()];
+        let mut rolled = shares[0];
+        for (i, share) in shares.iter().enumerate().take(recipients.len()).skip(1) {
+            if rent.is_exempt(*share, 0) {
+                expected[i] = *share;
+            } else {
+                rolled += share;
+            }
+        }
+        if rent.is_exempt(rolled, 0) {
+            expected[0] = rolled;
+        }
+        for (r, expected) in recipients.iter().zip(expected) {
+            let got = h.lamports(*r).await;
+            if got != expected {
+                return Err(format!("fee recipient ended with {got}, expected {expected}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn expect_error(got: Option<u32>, expected: u32, what: &str) -> std::result::Result<(), String> {
+    if got != Some(expected) {
+        return Err(format!("{what}: expected error {expected}, got {got:?}"));
+    }
+    Ok(())
+}
+
+fn bet_strategy() -> impl Strategy<Value = Bet> {
+    (
+        0u8..=1,
+        // Tiny stakes make the pro-rata split round; large ones stress the u128 math.
+        prop_oneof![1u64..=1_000, 1_000_000u64..=20_000_000_000],
+        any::<bool>(),
+        prop::bool::weighted(0.7),
+    )
+        .prop_map(|(side, amount, referred, claims)| Bet { side, amount, referred, claims })
+}
+
+fn weights_strategy() -> impl Strategy<Value = Vec<u16>> {
+    prop_oneof![
+        Just(Vec::new()),
+        (1..=MAX_FEE_RECIPIENTS).prop_flat_map(|n| {
+            prop::collection::vec(1u16..=1_000, n).prop_map(|raw| {
+                let total: u32 = raw.iter().map(|w| *w as u32).sum();
+                let mut weights: Vec<u16> = raw.iter().map(|w| (*w as u32 * 10_000 / total) as u16).collect();
+                let assigned: u16 = weights.iter().sum();
+                weights[0] += 10_000 - assigned;
+                weights
+            })
+        }),
+    ]
+}
+
+fn scenario_strategy() -> impl Strategy<Value = Scenario> {
+    (
+        0u16..=(10_000 - HOST_FEE_BPS_DEFAULT),
+        0u16..=10_000,
+        prop_oneof![Just(NoWinnerPolicy::Fees), Just(NoWinnerPolicy::Refund), Just(NoWinnerPolicy::Rollover)],
+        weights_strategy(),
+        prop::collection::vec(bet_strategy(), 1..=MAX_BETTORS),
+        prop_oneof![Just(0u8), Just(1u8), Just(SIDE_VOID)],
+        any::<bool>(),
+        prop_oneof![Just(UnclaimedPolicy::Treasury), Just(UnclaimedPolicy::Authority)],
+        any::<bool>(),
+    )
+        .prop_map(
+            |(fee_bps, referral_bps, no_winner_policy, weights, bets, outcome, claim_window, unclaimed_policy, crank)| {
+                Scenario { fee_bps, referral_bps, no_winner_policy, weights, bets, outcome, claim_window, unclaimed_policy, crank }
+            },
+        )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn escrow_conserves_lamports(s in scenario_strategy()) {
+        runtime().block_on(run(s)).map_err(TestCaseError::fail)?;
+    }
+}
+
+/// Three equal winners splitting an odd pool: the last claim must take the rounding dust, or
+/// `close_market` fails with OutstandingLamports.
+#[test]
+fn last_claim_takes_the_rounding_dust() {
+    let bet = |side, amount| Bet { side, amount, referred: false, claims: true };
+    let s = Scenario {
+        fee_bps: 0,
+        referral_bps: 0,
+        no_winner_policy: NoWinnerPolicy::Fees,
+        weights: Vec::new(),
+        bets: vec![bet(0, 1), bet(0, 1), bet(0, 1), bet(1, 2)],
+        outcome: 0,
+        claim_window: false,
+        unclaimed_policy: UnclaimedPolicy::Treasury,
+        crank: true,
+    };
+    runtime().block_on(run(s)).unwrap();
+}
+
+/// Two recipients' shares are far below rent exemption and go to the remainder recipient, which
+/// would otherwise leave the unfunded wallets unpayable and the market unclosable.
+#[test]
+fn sub_rent_fee_shares_roll_into_the_remainder_recipient() {
+    let bet = |side| Bet { side, amount: 10_000_000_000, referred: false, claims: true };
+    let s = Scenario {
+        fee_bps: 100,
+        referral_bps: 0,
+        no_winner_policy: NoWinnerPolicy::Fees,
+        weights: vec![9_990, 5, 5],
+        bets: vec![bet(0), bet(1)],
+        outcome: 0,
+        claim_window: false,
+        unclaimed_policy: UnclaimedPolicy::Treasury,
+        crank: true,
+    };
+    runtime().block_on(run(s)).unwrap();
+}
+
+/// Fees too small for any unfunded recipient end up in the treasury rather than stuck in the market.
+#[test]
+fn fee_shares_no_recipient_can_take_go_to_the_treasury() {
+    let bet = |side| Bet { side, amount: 1_000, referred: false, claims: true };
+    let s = Scenario {
+        fee_bps: 500,
+        referral_bps: 0,
+        no_winner_policy: NoWinnerPolicy::Fees,
+        weights: vec![5_000, 5_000],
+        bets: vec![bet(0), bet(1)],
+        outcome: 0,
+        claim_window: false,
+        unclaimed_policy: UnclaimedPolicy::Treasury,
+        crank: false,
+    };
+    runtime().block_on(run(s)).unwrap();
+}
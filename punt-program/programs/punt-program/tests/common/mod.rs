@@ -0,0 +1,313 @@
+//! Shared `solana-program-test` harness: a native bank running the real program, with helpers
+//! for the market lifecycle. Each test binary uses a different subset of it.
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use punt_program::{accounts, instruction, math, BetError, BetMarket, BetTicket, MarketOptions, ResolutionSource, ID};
+use solana_account::Account as SolanaAccount;
+use solana_keypair::Keypair;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+pub const STARTING_LAMPORTS: u64 = 1_000_000_000_000;
+
+// `entry` ties the slice lifetime to the accounts' own; the runtime only lends them for the call.
+pub fn process<'a, 'b>(program_id: &Pubkey, accounts: &'a [AccountInfo<'b>], data: &[u8]) -> ProgramResult {
+    let accounts = unsafe { std::mem::transmute::<&'a [AccountInfo<'b>], &'a [AccountInfo<'a>]>(accounts) };
+    punt_program::entry(program_id, accounts, data)
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &ID).0
+}
+
+pub fn market_pda(authority: &Pubkey, cycle: u64) -> Pubkey {
+    pda(&[b"market", authority.as_ref(), &cycle.to_le_bytes()])
+}
+
+pub fn ticket_pda(market: &Pubkey, user: &Pubkey) -> Pubkey {
+    pda(&[b"ticket", market.as_ref(), user.as_ref()])
+}
+
+pub fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+pub struct Harness {
+    pub ctx: ProgramTestContext,
+    pub authority: Keypair,
+    pub cranker: Keypair,
+    pub referrer: Keypair,
+    pub treasury: Pubkey,
+    pub referrer_vault: Pubkey,
+    pub tracked: Vec<Pubkey>,
+}
+
+impl Harness {
+    /// Starts a bank with `wallets` funded, the treasury, the authority's meta and a referrer vault.
+    pub async fn start(wallets: &[Pubkey]) -> Self {
+        let mut test = ProgramTest::new("punt_program", ID, processor!(process));
+        let authority = Keypair::new();
+        let cranker = Keypair::new();
+        let referrer = Keypair::new();
+        for key in [authority.pubkey(), cranker.pubkey(), referrer.pubkey()].iter().chain(wallets) {
+            test.add_account(*key, funded(STARTING_LAMPORTS));
+        }
+        let ctx = test.start_with_context().await;
+        let treasury = pda(&[b"treasury"]);
+        let referrer_vault = pda(&[b"referrer_vault", referrer.pubkey().as_ref()]);
+        let mut h = Self { ctx, authority, cranker, referrer, treasury, referrer_vault, tracked: Vec::new() };
+        let payer = h.ctx.payer.pubkey();
+        h.send(build(accounts::InitTreasury { payer, treasury, system_program: system_program::ID }, instruction::InitTreasury {}), &[])
+            .await
+            .unwrap();
+        let authority_key = h.authority.pubkey();
+        h.send(
+            build(
+                accounts::InitAuthorityMeta {
+                    authority: authority_key,
+                    authority_meta: pda(&[b"authority_meta", authority_key.as_ref()]),
+                    system_program: system_program::ID,
+                },
+                instruction::InitAuthorityMeta {},
+            ),
+            &[&h.authority.insecure_clone()],
+        )
+        .await
+        .unwrap();
+        let referrer_key = h.referrer.pubkey();
+        h.send(
+            build(
+                accounts::InitReferrerVault { referrer: referrer_key, referrer_vault, system_program: system_program::ID },
+                instruction::InitReferrerVault {},
+            ),
+            &[&h.referrer.insecure_clone()],
+        )
+        .await
+        .unwrap();
+        h.tracked = vec![authority_key, h.cranker.pubkey(), referrer_key, treasury, referrer_vault];
+        h.tracked.extend_from_slice(wallets);
+        h
+    }
+
+    pub fn transaction(&self, ix: Instruction, signers: &[&Keypair]) -> Transaction {
+        let mut all = vec![&self.ctx.payer];
+        all.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(&[ix], Some(&self.ctx.payer.pubkey()), &all, self.ctx.last_blockhash)
+    }
+
+    pub async fn send(&mut self, ix: Instruction, signers: &[&Keypair]) -> std::result::Result<(), BanksClientError> {
+        let tx = self.transaction(ix, signers);
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// Simulates `ix` and returns the program error it fails with.
+    pub async fn rejection(&mut self, ix: Instruction, signers: &[&Keypair]) -> Option<u32> {
+        let tx = self.transaction(ix, signers);
+        match self.ctx.banks_client.simulate_transaction(tx).await.unwrap().result {
+            Some(Err(TransactionError::InstructionError(_, InstructionError::Custom(code)))) => Some(code),
+            _ => None,
+        }
+    }
+
+    pub async fn lamports(&mut self, key: Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(key).await.unwrap()
+    }
+
+    pub async fn total(&mut self) -> u64 {
+        let mut sum = 0;
+        for key in self.tracked.clone() {
+            sum += self.lamports(key).await;
+        }
+        sum
+    }
+
+    pub async fn exists(&mut self, key: Pubkey) -> bool {
+        self.ctx.banks_client.get_account(key).await.unwrap().is_some()
+    }
+
+    pub async fn load<T: AccountDeserialize>(&mut self, key: Pubkey) -> T {
+        let account = self.ctx.banks_client.get_account(key).await.unwrap().expect("account exists");
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn warp_to(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// The escrow always covers its rent, accrued fees and any pending rollover.
+    pub async fn check_escrow(&mut self, market: Pubkey) -> std::result::Result<(), String> {
+        let state: BetMarket = self.load(market).await;
+        let rent = self.ctx.banks_client.get_rent().await.unwrap().minimum_balance(8 + BetMarket::SIZE);
+        let held = self.lamports(market).await;
+        if held < rent + state.fees_accrued + state.rollover_pending {
+            return Err(format!(
+                "escrow {held} below rent {rent} + fees {} + rollover {}",
+                state.fees_accrued, state.rollover_pending
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn open_market(&mut self, cycle: u64, fee_bps: u16, options: MarketOptions) -> Pubkey {
+        let authority = self.authority.pubkey();
+        let market = market_pda(&authority, cycle);
+        let ix = build(
+            accounts::InitializeMarket {
+                authority,
+                authority_meta: pda(&[b"authority_meta", authority.as_ref()]),
+                market,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeMarket {
+                cycle,
+                title: "Test market".into(),
+                label_yes: "Yes".into(),
+                label_no: "No".into(),
+                fee_bps: Some(fee_bps),
+                options: Some(options),
+            },
+        );
+        self.send(ix, &[&self.authority.insecure_clone()]).await.unwrap();
+        self.tracked.push(market);
+        market
+    }
+
+    pub async fn bet(&mut self, market: Pubkey, user: &Keypair, side: u8, amount: u64, referred: bool) {
+        let ticket = ticket_pda(&market, &user.pubkey());
+        let referrer = referred.then(|| self.referrer.pubkey());
+        let create = build(
+            accounts::CreateTicket {
+                user: user.pubkey(),
+                market,
+                ticket,
+                referrer_vault: referred.then_some(self.referrer_vault),
+                system_program: system_program::ID,
+            },
+            instruction::CreateTicket { side, referrer },
+        );
+        self.send(create, &[user]).await.unwrap();
+        let place = build(
+            accounts::PlaceBet { user: user.pubkey(), market, ticket, system_program: system_program::ID },
+            instruction::PlaceBet { amount },
+        );
+        self.send(place, &[user]).await.unwrap();
+        self.tracked.push(ticket);
+    }
+
+    pub async fn freeze(&mut self, market: Pubkey) {
+        let authority = self.authority.insecure_clone();
+        let freeze = build(
+            accounts::FreezeMarket { authority: authority.pubkey(), market, slot_hashes: sysvar::slot_hashes::ID },
+            instruction::FreezeMarket {},
+        );
+        self.send(freeze, &[&authority]).await.unwrap();
+    }
+
+    pub async fn freeze_and_resolve(&mut self, market: Pubkey, winning_side: u8) {
+        self.freeze(market).await;
+        let authority = self.authority.insecure_clone();
+        let resolve = build(
+            accounts::ResolveMarket { resolver: authority.pubkey(), market },
+            instruction::ResolveMarket {
+                winning_side,
+                evidence_hash: [0; 32],
+                confidence_bps: 0,
+                source: ResolutionSource::Manual,
+            },
+        );
+        self.send(resolve, &[&authority]).await.unwrap();
+    }
+
+    pub async fn claim(&mut self, market: Pubkey, user: &Pubkey) -> std::result::Result<(), BanksClientError> {
+        let ticket = ticket_pda(&market, user);
+        let state: BetTicket = self.load(ticket).await;
+        let ix = build(
+            accounts::ClaimWinnings {
+                user: *user,
+                market,
+                ticket,
+                referrer_vault: (state.referrer != Pubkey::default()).then_some(self.referrer_vault),
+            },
+            instruction::ClaimWinnings {},
+        );
+        self.send(ix, &[]).await
+    }
+
+    pub async fn close_ticket(&mut self, market: Pubkey, user: &Pubkey) -> std::result::Result<(), BanksClientError> {
+        let ix = build(
+            accounts::CloseTicket { user: *user, market, ticket: ticket_pda(&market, user) },
+            instruction::CloseTicket {},
+        );
+        self.send(ix, &[]).await
+    }
+
+    pub fn caller(&self, crank: bool) -> Keypair {
+        if crank { self.cranker.insecure_clone() } else { self.authority.insecure_clone() }
+    }
+
+    pub fn withdraw_fees_ix(&self, caller: &Pubkey, market: Pubkey, recipients: &[Pubkey]) -> Instruction {
+        let mut ix = build(
+            accounts::WithdrawFees {
+                caller: *caller,
+                authority: self.authority.pubkey(),
+                market,
+                treasury: self.treasury,
+                fee_schedule: (!recipients.is_empty()).then(|| pda(&[b"fee_schedule", market.as_ref()])),
+            },
+            instruction::WithdrawFees {},
+        );
+        ix.accounts.extend(recipients.iter().map(|r| AccountMeta::new(*r, false)));
+        ix
+    }
+
+    pub fn close_market_ix(&self, caller: &Pubkey, market: Pubkey, recipients: &[Pubkey]) -> Instruction {
+        let mut ix = build(
+            accounts::CloseMarket {
+                caller: *caller,
+                authority: self.authority.pubkey(),
+                market,
+                treasury: self.treasury,
+                fee_schedule: (!recipients.is_empty()).then(|| pda(&[b"fee_schedule", market.as_ref()])),
+            },
+            instruction::CloseMarket {},
+        );
+        ix.accounts.extend(recipients.iter().map(|r| AccountMeta::new(*r, false)));
+        ix
+    }
+
+    /// Withdraws any accrued fees, then closes the market. Returns the authority fee share paid.
+    pub async fn settle(&mut self, market: Pubkey, recipients: &[Pubkey], crank: bool) -> std::result::Result<u64, String> {
+        let caller = self.caller(crank);
+        let state: BetMarket = self.load(market).await;
+        let mut authority_share = 0;
+        if state.fees_accrued > 0 {
+            authority_share = math::split_fees(state.fees_accrued, state.fee_bps, state.host_fee_bps).0;
+            let ix = self.withdraw_fees_ix(&caller.pubkey(), market, recipients);
+            self.send(ix, &[&caller]).await.map_err(|e| format!("withdraw_fees: {e}"))?;
+        }
+        let ix = self.close_market_ix(&caller.pubkey(), market, recipients);
+        self.send(ix, &[&caller]).await.map_err(|e| format!("close_market: {e}"))?;
+        Ok(authority_share)
+    }
+}
+
+pub fn funded(lamports: u64) -> SolanaAccount {
+    SolanaAccount { lamports, owner: system_program::ID, ..Default::default() }
+}
+
+pub fn code(error: BetError) -> u32 {
+    error.into()
+}
+
+pub fn runtime() -> solana_program_test::tokio::runtime::Runtime {
+    solana_program_test::tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap()
+}
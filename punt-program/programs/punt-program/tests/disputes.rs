@@ -0,0 +1,139 @@
+//! Dispute paths that need the clock moved past the arbitration window, which a local validator
+//! cannot do: a challenge the arbiter never rules on keeps the result once `expire_dispute` runs.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use common::*;
+use punt_program::{
+    accounts, instruction, math, BetError, BetMarket, DisputeConfig, MarketOptions, ResolutionSource, DISPUTE_CHALLENGED,
+    DISPUTE_EXPIRED,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const STAKE: u64 = 100_000_000;
+const BOND: u64 = 50_000_000;
+
+fn bond_pda(market: &Pubkey, challenger: &Pubkey) -> Pubkey {
+    pda(&[b"bond", market.as_ref(), challenger.as_ref()])
+}
+
+/// A resolved yes/no market with one bet on each side, the loser (`doubter`) having challenged.
+async fn challenged_market(h: &mut Harness, backer: &Keypair, doubter: &Keypair) -> (Pubkey, Pubkey) {
+    let options = MarketOptions { dispute: DisputeConfig { window_secs: 3_600, bond: BOND }, ..Default::default() };
+    let market = h.open_market(0, 0, options).await;
+    h.bet(market, backer, 0, STAKE, false).await;
+    h.bet(market, doubter, 1, STAKE, false).await;
+    h.freeze_and_resolve(market, 0).await;
+
+    let bond = bond_pda(&market, &doubter.pubkey());
+    let challenge = build(
+        accounts::ChallengeResolution {
+            challenger: doubter.pubkey(),
+            market,
+            ticket: ticket_pda(&market, &doubter.pubkey()),
+            bond,
+            system_program: system_program::ID,
+        },
+        instruction::ChallengeResolution {},
+    );
+    h.send(challenge, &[doubter]).await.unwrap();
+    let state: BetMarket = h.load(market).await;
+    assert_eq!(state.dispute_state, DISPUTE_CHALLENGED);
+    assert_eq!(state.open_bonds, 1);
+    (market, bond)
+}
+
+fn expire(market: Pubkey) -> Instruction {
+    build(accounts::ExpireDispute { market }, instruction::ExpireDispute {})
+}
+
+fn settle_bond(market: Pubkey, challenger: &Keypair) -> Instruction {
+    let bond = bond_pda(&market, &challenger.pubkey());
+    build(
+        accounts::SettleDisputeBond { challenger: challenger.pubkey(), market, bond },
+        instruction::SettleDisputeBond {},
+    )
+}
+
+#[test]
+fn unruled_challenge_expires_and_keeps_the_result() {
+    runtime().block_on(async {
+        let backer = Keypair::new();
+        let doubter = Keypair::new();
+        let mut h = Harness::start(&[backer.pubkey(), doubter.pubkey()]).await;
+        let (market, bond) = challenged_market(&mut h, &backer, &doubter).await;
+
+        let state: BetMarket = h.load(market).await;
+        let deadline = state.arbitration_deadline();
+        h.warp_to(deadline - 1).await;
+        assert_eq!(h.rejection(expire(market), &[]).await, Some(code(BetError::ArbitrationOpen)));
+        let claim = build(
+            accounts::ClaimWinnings {
+                user: backer.pubkey(),
+                market,
+                ticket: ticket_pda(&market, &backer.pubkey()),
+                referrer_vault: None,
+            },
+            instruction::ClaimWinnings {},
+        );
+        assert_eq!(h.rejection(claim, &[]).await, Some(code(BetError::DisputePending)));
+
+        h.warp_to(deadline).await;
+        h.send(expire(market), &[]).await.unwrap();
+        let state: BetMarket = h.load(market).await;
+        assert_eq!(state.dispute_state, DISPUTE_EXPIRED);
+        assert_eq!(state.winning_side, 0);
+        assert_eq!(state.resolution_source, ResolutionSource::Manual);
+
+        // No ruling means no slash: the bond and its rent go back to the challenger.
+        let held = h.lamports(bond).await;
+        assert!(held > BOND);
+        let before = h.lamports(doubter.pubkey()).await;
+        h.send(settle_bond(market, &doubter), &[]).await.unwrap();
+        assert_eq!(h.lamports(doubter.pubkey()).await, before + held);
+        assert!(!h.exists(bond).await);
+        let state: BetMarket = h.load(market).await;
+        assert_eq!(state.fees_accrued, 0);
+        assert_eq!(state.open_bonds, 0);
+
+        let (payout, _) = math::net_payout(2 * STAKE, STAKE, state.fee_bps, state.host_fee_bps).unwrap();
+        let ticket = ticket_pda(&market, &backer.pubkey());
+        let before = h.lamports(backer.pubkey()).await + h.lamports(ticket).await;
+        h.claim(market, &backer.pubkey()).await.unwrap();
+        assert_eq!(h.lamports(backer.pubkey()).await, before + payout);
+    });
+}
+
+/// Closing the market first would strand the bond: `settle_dispute_bond` needs the market.
+#[test]
+fn market_stays_open_until_every_bond_is_settled() {
+    runtime().block_on(async {
+        let backer = Keypair::new();
+        let doubter = Keypair::new();
+        let mut h = Harness::start(&[backer.pubkey(), doubter.pubkey()]).await;
+        let (market, bond) = challenged_market(&mut h, &backer, &doubter).await;
+        let state: BetMarket = h.load(market).await;
+        h.warp_to(state.arbitration_deadline()).await;
+        h.send(expire(market), &[]).await.unwrap();
+        h.claim(market, &backer.pubkey()).await.unwrap();
+        h.close_ticket(market, &doubter.pubkey()).await.unwrap();
+
+        // Everything else is settled; only the bond holds the market open, for the authority and cranks alike.
+        let withdraw = h.withdraw_fees_ix(&h.authority.pubkey(), market, &[]);
+        h.send(withdraw, &[&h.authority.insecure_clone()]).await.unwrap();
+        for crank in [false, true] {
+            let caller = h.caller(crank);
+            let close = h.close_market_ix(&caller.pubkey(), market, &[]);
+            assert_eq!(h.rejection(close, &[&caller]).await, Some(code(BetError::BondsOutstanding)));
+        }
+
+        h.send(settle_bond(market, &doubter), &[]).await.unwrap();
+        assert!(!h.exists(bond).await);
+        h.settle(market, &[], true).await.unwrap();
+        assert!(!h.exists(market).await);
+    });
+}
@@ -0,0 +1,63 @@
+//! Market ids are client-chosen and only need to be free: an id whose market was closed can be
+//! taken again. Tickets the old market left behind sit at the same addresses, so they must not be
+//! adopted by the new one.
+
+mod common;
+
+use anchor_lang::system_program;
+use common::*;
+use punt_program::{accounts, instruction, BetError, BetMarket, BetTicket, MarketOptions};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const STAKE: u64 = 100_000_000;
+
+#[test]
+fn reused_id_does_not_adopt_the_closed_markets_tickets() {
+    runtime().block_on(async {
+        let backer = Keypair::new();
+        let doubter = Keypair::new();
+        let mut h = Harness::start(&[backer.pubkey(), doubter.pubkey()]).await;
+        let market = h.open_market(0, 0, MarketOptions::default()).await;
+        h.bet(market, &backer, 0, STAKE, false).await;
+        h.bet(market, &doubter, 1, STAKE, false).await;
+        h.freeze_and_resolve(market, 0).await;
+        h.claim(market, &backer.pubkey()).await.unwrap();
+        // The losing ticket is never closed and outlives its market.
+        h.settle(market, &[], false).await.unwrap();
+        assert!(!h.exists(market).await);
+
+        // Stakes and fee differ from the first market's, or the bank would dedupe the repeated transactions.
+        let reopened = h.open_market(0, 10, MarketOptions::default()).await;
+        assert_eq!(reopened, market);
+        let state: BetMarket = h.load(market).await;
+        let ticket = ticket_pda(&market, &doubter.pubkey());
+        let stale: BetTicket = h.load(ticket).await;
+        assert_eq!((stale.market_serial, state.serial), (1, 2));
+
+        let place = build(
+            accounts::PlaceBet { user: doubter.pubkey(), market, ticket, system_program: system_program::ID },
+            instruction::PlaceBet { amount: 2 * STAKE },
+        );
+        assert_eq!(h.rejection(place, &[&doubter]).await, Some(code(BetError::TicketMarketMismatch)));
+        let close = build(accounts::CloseTicket { user: doubter.pubkey(), market, ticket }, instruction::CloseTicket {});
+        assert_eq!(h.rejection(close, &[]).await, Some(code(BetError::TicketMarketMismatch)));
+
+        // It is still an orphan: its rent goes back, and the owner can then bet on the new market.
+        let before = h.lamports(doubter.pubkey()).await + h.lamports(ticket).await;
+        let orphan = build(accounts::CloseOrphanTicket { user: doubter.pubkey(), market, ticket }, instruction::CloseOrphanTicket {});
+        h.send(orphan, &[]).await.unwrap();
+        assert_eq!(h.lamports(doubter.pubkey()).await, before);
+        h.bet(market, &doubter, 0, 2 * STAKE, false).await;
+        let fresh: BetTicket = h.load(ticket).await;
+        assert_eq!(fresh.market_serial, state.serial);
+
+        // A ticket of the live market is no orphan.
+        let orphan = build(
+            accounts::CloseOrphanTicket { user: backer.pubkey(), market, ticket: ticket_pda(&market, &backer.pubkey()) },
+            instruction::CloseOrphanTicket {},
+        );
+        h.bet(market, &backer, 1, 2 * STAKE, false).await;
+        assert_eq!(h.rejection(orphan, &[]).await, Some(code(BetError::MarketStillOpen)));
+    });
+}
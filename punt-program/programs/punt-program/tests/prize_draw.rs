@@ -0,0 +1,70 @@
+//! The prize draw is walked by whoever holds the seed, page by page: these cases check that a page
+//! must continue exactly where the walk stopped, so nobody can restart or reorder it, and that the
+//! winner is the one the committed seed and the freeze-time entropy pick.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use common::*;
+use punt_program::{accounts, instruction, BetError, BetMarket, BetTicket, MarketOptions, PrizeWeighting};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const STAKE: u64 = 100_000_000;
+const SEED: [u8; 32] = [7; 32];
+
+fn draw(market: Pubkey, seed: [u8; 32], tickets: &[Pubkey]) -> Instruction {
+    let mut ix = build(accounts::DrawPrizeWinner { market }, instruction::DrawPrizeWinner { seed });
+    ix.accounts.extend(tickets.iter().map(|t| AccountMeta::new_readonly(*t, false)));
+    ix
+}
+
+#[test]
+fn draw_walks_winning_tickets_in_entry_order() {
+    runtime().block_on(async {
+        let backers: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+        let doubter = Keypair::new();
+        let wallets: Vec<Pubkey> = backers.iter().chain([&doubter]).map(Signer::pubkey).collect();
+        let mut h = Harness::start(&wallets).await;
+        let market = h.open_market(0, 0, MarketOptions::default()).await;
+
+        let authority = h.authority.insecure_clone();
+        let commit = build(
+            accounts::CommitPrizeSeed { authority: authority.pubkey(), market },
+            instruction::CommitPrizeSeed { seed_hash: hash(&SEED).to_bytes(), weighting: PrizeWeighting::PerTicket },
+        );
+        h.send(commit, &[&authority]).await.unwrap();
+        for backer in &backers {
+            h.bet(market, backer, 0, STAKE, false).await;
+        }
+        h.bet(market, &doubter, 1, STAKE, false).await;
+
+        let tickets: Vec<Pubkey> = backers.iter().map(|b| ticket_pda(&market, &b.pubkey())).collect();
+        for (index, ticket) in tickets.iter().enumerate() {
+            let state: BetTicket = h.load(*ticket).await;
+            assert_eq!(state.entry_index as usize, index);
+        }
+        let state: BetMarket = h.load(market).await;
+        assert_eq!(state.prize_entropy, [0; 32], "no entropy before the freeze");
+        h.freeze_and_resolve(market, 0).await;
+        let state: BetMarket = h.load(market).await;
+        assert_ne!(state.prize_entropy, [0; 32], "entropy is taken at the freeze");
+
+        let rejected = Some(code(BetError::PrizeEntrantsInvalid));
+        assert_eq!(h.rejection(draw(market, SEED, &tickets[1..]), &[]).await, rejected, "must start at entry 0");
+        h.send(draw(market, SEED, &tickets[..1]), &[]).await.unwrap();
+        // Anyone could send a page from the start again; it is refused instead of resetting the walk.
+        assert_eq!(h.rejection(draw(market, SEED, &tickets[..2]), &[]).await, rejected, "restart");
+        assert_eq!(h.rejection(draw(market, SEED, &tickets[2..]), &[]).await, rejected, "skipped entry");
+        let state: BetMarket = h.load(market).await;
+        assert_eq!((state.prize_counted, state.prize_winner), (1, Pubkey::default()));
+
+        h.send(draw(market, SEED, &tickets[1..]), &[]).await.unwrap();
+        let state: BetMarket = h.load(market).await;
+        let mix = hashv(&[&SEED, &state.prize_entropy, market.as_ref()]).to_bytes();
+        let target = (u64::from_le_bytes(mix[..8].try_into().unwrap()) as u128 * backers.len() as u128) >> 64;
+        assert_eq!(state.prize_winner, backers[target as usize].pubkey());
+    });
+}
@@ -0,0 +1,53 @@
+//! A no-winner pool under `NoWinnerPolicy::Rollover` waits for the authority to roll it into a
+//! later market. If that never happens, `expire_rollover` sends it to the treasury after
+//! ROLLOVER_TIMEOUT_SECS so the market can still close; these cases warp the clock to get there.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use common::*;
+use punt_program::{accounts, instruction, BetError, BetMarket, MarketOptions, NoWinnerPolicy, Treasury, ROLLOVER_TIMEOUT_SECS};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const STAKE: u64 = 100_000_000;
+
+fn expire(market: Pubkey) -> Instruction {
+    build(accounts::ExpireRollover { market, treasury: pda(&[b"treasury"]) }, instruction::ExpireRollover {})
+}
+
+#[test]
+fn rollover_without_a_later_market_expires_to_the_treasury() {
+    runtime().block_on(async {
+        let doubter = Keypair::new();
+        let mut h = Harness::start(&[doubter.pubkey()]).await;
+        let options = MarketOptions { no_winner_policy: NoWinnerPolicy::Rollover, ..Default::default() };
+        let market = h.open_market(0, 0, options).await;
+        h.bet(market, &doubter, 1, STAKE, false).await;
+        h.freeze_and_resolve(market, 0).await;
+        h.close_ticket(market, &doubter.pubkey()).await.unwrap();
+        let state: BetMarket = h.load(market).await;
+        assert_eq!(state.rollover_pending, STAKE);
+
+        let close = h.close_market_ix(&h.authority.pubkey(), market, &[]);
+        let authority = h.authority.insecure_clone();
+        assert_eq!(h.rejection(close, &[&authority]).await, Some(code(BetError::RolloverPending)));
+        h.warp_to(state.resolved_at + ROLLOVER_TIMEOUT_SECS - 1).await;
+        assert_eq!(h.rejection(expire(market), &[]).await, Some(code(BetError::RolloverWindowOpen)));
+
+        h.warp_to(state.rollover_deadline()).await;
+        let before: Treasury = h.load(h.treasury).await;
+        let held = h.lamports(h.treasury).await;
+        h.send(expire(market), &[]).await.unwrap();
+        let after: Treasury = h.load(h.treasury).await;
+        assert_eq!(after.collected, before.collected + STAKE);
+        assert_eq!(h.lamports(h.treasury).await, held + STAKE);
+        let state: BetMarket = h.load(market).await;
+        assert_eq!(state.rollover_pending, 0);
+        h.check_escrow(market).await.unwrap();
+
+        h.settle(market, &[], false).await.unwrap();
+        assert!(!h.exists(market).await);
+    });
+}
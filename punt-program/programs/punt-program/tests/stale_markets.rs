@@ -0,0 +1,63 @@
+//! `void_stale_market` only succeeds RESOLUTION_TIMEOUT_SECS after betting closed, which a local
+//! validator cannot wait out: these cases warp the clock instead.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use common::*;
+use punt_program::{
+    accounts, instruction, BetError, BetMarket, MarketOptions, ResolutionSource, RESOLUTION_TIMEOUT_SECS, SIDE_VOID,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const STAKE: u64 = 100_000_000;
+
+fn void_stale(market: Pubkey) -> Instruction {
+    build(accounts::VoidStaleMarket { market }, instruction::VoidStaleMarket {})
+}
+
+/// Voids `market` once stale and checks `bettor` gets the stake (and ticket rent) back in full.
+async fn void_and_refund(h: &mut Harness, market: Pubkey, bettor: &Keypair, closed_at: i64) {
+    h.warp_to(closed_at + RESOLUTION_TIMEOUT_SECS - 1).await;
+    assert_eq!(h.rejection(void_stale(market), &[]).await, Some(code(BetError::ResolveTooEarly)));
+
+    h.warp_to(closed_at + RESOLUTION_TIMEOUT_SECS).await;
+    h.send(void_stale(market), &[]).await.unwrap();
+    let state: BetMarket = h.load(market).await;
+    assert!(state.frozen && state.resolved);
+    assert_eq!(state.winning_side, SIDE_VOID);
+    assert_eq!(state.resolution_source, ResolutionSource::Timeout);
+
+    let ticket = ticket_pda(&market, &bettor.pubkey());
+    let before = h.lamports(bettor.pubkey()).await + h.lamports(ticket).await;
+    h.claim(market, &bettor.pubkey()).await.unwrap();
+    assert_eq!(h.lamports(bettor.pubkey()).await, before + STAKE);
+}
+
+#[test]
+fn frozen_market_left_unresolved_is_voided() {
+    runtime().block_on(async {
+        let bettor = Keypair::new();
+        let mut h = Harness::start(&[bettor.pubkey()]).await;
+        let market = h.open_market(0, 0, MarketOptions::default()).await;
+        h.bet(market, &bettor, 0, STAKE, false).await;
+        h.freeze(market).await;
+        let state: BetMarket = h.load(market).await;
+        void_and_refund(&mut h, market, &bettor, state.frozen_at).await;
+    });
+}
+
+#[test]
+fn market_past_close_ts_is_voided_without_a_freeze() {
+    runtime().block_on(async {
+        let bettor = Keypair::new();
+        let mut h = Harness::start(&[bettor.pubkey()]).await;
+        let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+        let close_ts = clock.unix_timestamp + 60;
+        let market = h.open_market(0, 0, MarketOptions { close_ts, ..Default::default() }).await;
+        h.bet(market, &bettor, 1, STAKE, false).await;
+        void_and_refund(&mut h, market, &bettor, close_ts).await;
+    });
+}
@@ -0,0 +1,49 @@
+//! `claim_winnings` and `close_ticket` need no signature from the ticket owner, so keepers can
+//! crank them. That is only safe because the owner is pinned by the ticket: whoever sends the
+//! transaction, the payout and the ticket rent can only land in the owner's wallet.
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use punt_program::{accounts, instruction, MarketOptions};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const STAKE: u64 = 100_000_000;
+
+#[test]
+fn cranked_claims_and_closes_pay_only_the_ticket_owner() {
+    runtime().block_on(async {
+        let (backer, doubter, stranger) = (Keypair::new(), Keypair::new(), Keypair::new());
+        let mut h = Harness::start(&[backer.pubkey(), doubter.pubkey(), stranger.pubkey()]).await;
+        let market = h.open_market(0, 0, MarketOptions::default()).await;
+        h.bet(market, &backer, 0, STAKE, false).await;
+        h.bet(market, &doubter, 1, STAKE, false).await;
+        h.freeze_and_resolve(market, 0).await;
+        let (won, lost) = (ticket_pda(&market, &backer.pubkey()), ticket_pda(&market, &doubter.pubkey()));
+
+        // Naming another wallet as `user` does not redirect someone else's ticket to it: the ticket is
+        // derived from its owner.
+        let claim = build(
+            accounts::ClaimWinnings { user: stranger.pubkey(), market, ticket: won, referrer_vault: None },
+            instruction::ClaimWinnings {},
+        );
+        assert_eq!(h.rejection(claim, &[]).await, Some(ErrorCode::ConstraintSeeds.into()));
+        let close = build(accounts::CloseTicket { user: stranger.pubkey(), market, ticket: lost }, instruction::CloseTicket {});
+        assert_eq!(h.rejection(close, &[]).await, Some(ErrorCode::ConstraintSeeds.into()));
+
+        // Cranked without the owners' signatures, everything leaving the market and tickets goes to them.
+        let stranger_before = h.lamports(stranger.pubkey()).await;
+        let before = h.lamports(backer.pubkey()).await + h.lamports(won).await + h.lamports(market).await;
+        h.claim(market, &backer.pubkey()).await.unwrap();
+        assert!(!h.exists(won).await);
+        assert_eq!(h.lamports(backer.pubkey()).await + h.lamports(market).await, before);
+
+        let before = h.lamports(doubter.pubkey()).await + h.lamports(lost).await;
+        h.close_ticket(market, &doubter.pubkey()).await.unwrap();
+        assert!(!h.exists(lost).await);
+        assert_eq!(h.lamports(doubter.pubkey()).await, before);
+        assert_eq!(h.lamports(stranger.pubkey()).await, stranger_before);
+    });
+}
@@ -0,0 +1,98 @@
+//! `treasury_withdraw` pays out only to `Treasury::admin` and only what the treasury collected.
+//! The admin starts as TREASURY_ADMIN_PUBKEY, whose key a test cannot sign with, so these cases
+//! rewrite the stored admin to a local keypair.
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use common::*;
+use punt_program::{accounts, instruction, BetError, MarketOptions, Treasury, HOST_PUBKEY, TREASURY_ADMIN_PUBKEY};
+use solana_account::AccountSharedData;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const STAKE: u64 = 1_000_000_000;
+
+fn withdraw(admin: &Keypair, destination: Pubkey, amount: u64) -> Instruction {
+    let treasury = pda(&[b"treasury"]);
+    build(
+        accounts::TreasuryWithdraw { admin: admin.pubkey(), treasury, destination },
+        instruction::TreasuryWithdraw { amount },
+    )
+}
+
+fn set_admin(admin: &Keypair, new_admin: Pubkey) -> Instruction {
+    let treasury = pda(&[b"treasury"]);
+    build(accounts::SetTreasuryAdmin { admin: admin.pubkey(), treasury }, instruction::SetTreasuryAdmin { new_admin })
+}
+
+/// Collects host fees from one settled market and hands the treasury to `admin`.
+async fn funded_treasury(h: &mut Harness, admin: &Keypair) -> Treasury {
+    let (backer, doubter) = (Keypair::new(), Keypair::new());
+    for key in [backer.pubkey(), doubter.pubkey()] {
+        h.ctx.set_account(&key, &funded(STARTING_LAMPORTS).into());
+    }
+    let market = h.open_market(0, 100, MarketOptions::default()).await;
+    h.bet(market, &backer, 0, STAKE, false).await;
+    h.bet(market, &doubter, 1, STAKE, false).await;
+    h.freeze_and_resolve(market, 0).await;
+    h.claim(market, &backer.pubkey()).await.unwrap();
+    h.close_ticket(market, &doubter.pubkey()).await.unwrap();
+    h.settle(market, &[], false).await.unwrap();
+
+    let mut treasury: Treasury = h.load(h.treasury).await;
+    assert_eq!(treasury.admin, Pubkey::from_str_const(TREASURY_ADMIN_PUBKEY));
+    assert_ne!(treasury.admin, Pubkey::from_str_const(HOST_PUBKEY), "a resolver key must not hold the treasury");
+    assert!(treasury.collected > 0);
+    treasury.admin = admin.pubkey();
+    let mut account = h.ctx.banks_client.get_account(h.treasury).await.unwrap().unwrap();
+    let mut data = Vec::new();
+    treasury.try_serialize(&mut data).unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+    h.ctx.set_account(&h.treasury, &AccountSharedData::from(account));
+    treasury
+}
+
+#[test]
+fn only_the_admin_withdraws_and_never_more_than_collected() {
+    runtime().block_on(async {
+        let admin = Keypair::new();
+        let mut h = Harness::start(&[admin.pubkey()]).await;
+        let treasury = funded_treasury(&mut h, &admin).await;
+        let available = treasury.collected - treasury.withdrawn;
+        let destination = Pubkey::new_unique();
+
+        // The market authority and a cranker are no more the admin than any other wallet.
+        for outsider in [h.authority.insecure_clone(), h.cranker.insecure_clone()] {
+            let ix = withdraw(&outsider, outsider.pubkey(), 1);
+            assert_eq!(h.rejection(ix, &[&outsider]).await, Some(code(BetError::Unauthorized)));
+        }
+        let ix = withdraw(&admin, destination, available + 1);
+        assert_eq!(h.rejection(ix, &[&admin]).await, Some(code(BetError::InsufficientEscrow)));
+
+        h.send(withdraw(&admin, destination, available), &[&admin]).await.unwrap();
+        assert_eq!(h.lamports(destination).await, available);
+        let after: Treasury = h.load(h.treasury).await;
+        assert_eq!(after.withdrawn, treasury.withdrawn + available);
+        // Everything collected is gone; the treasury keeps its rent.
+        let ix = withdraw(&admin, destination, 1);
+        assert_eq!(h.rejection(ix, &[&admin]).await, Some(code(BetError::InsufficientEscrow)));
+    });
+}
+
+#[test]
+fn admin_hands_the_treasury_to_a_new_key() {
+    runtime().block_on(async {
+        let (admin, successor) = (Keypair::new(), Keypair::new());
+        let mut h = Harness::start(&[admin.pubkey(), successor.pubkey()]).await;
+        funded_treasury(&mut h, &admin).await;
+
+        let ix = set_admin(&successor, successor.pubkey());
+        assert_eq!(h.rejection(ix, &[&successor]).await, Some(code(BetError::Unauthorized)));
+        h.send(set_admin(&admin, successor.pubkey()), &[&admin]).await.unwrap();
+        let ix = withdraw(&admin, admin.pubkey(), 1);
+        assert_eq!(h.rejection(ix, &[&admin]).await, Some(code(BetError::Unauthorized)));
+        h.send(withdraw(&successor, successor.pubkey(), 1), &[&successor]).await.unwrap();
+    });
+}
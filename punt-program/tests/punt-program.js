@@ -4,8 +4,14 @@ const anchor = require("@coral-xyz/anchor");
 const { PublicKey, SystemProgram, Keypair, LAMPORTS_PER_SOL } = anchor.web3;
 
 function cycleSeed(cycle) {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(cycle));
+  return buf;
+}
+
+function templateSeed(id) {
   const buf = Buffer.alloc(2);
-  buf.writeUInt16LE(cycle);
+  buf.writeUInt16LE(id);
   return buf;
 }
 
@@ -18,6 +24,122 @@ describe("stream-bets-program", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
   const program = anchor.workspace.StreamBetsProgram;
+  const systemProgram = SystemProgram.programId;
+
+  const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const authorityMetaPda = (authority) => pda(Buffer.from("authority_meta"), authority.publicKey.toBuffer());
+  const marketPda = (authority, cycle) => pda(Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(cycle));
+  const ticketPda = (market, user) => pda(Buffer.from("ticket"), market.toBuffer(), user.publicKey.toBuffer());
+  const votePda = (market) => pda(Buffer.from("vote"), market.toBuffer());
+  const treasury = pda(Buffer.from("treasury"));
+
+  async function fundedKeypair(sol = 2) {
+    const kp = Keypair.generate();
+    await requestAirdrop(provider.connection, kp.publicKey, sol);
+    return kp;
+  }
+
+  async function initAuthorityMeta(authority) {
+    const authorityMeta = authorityMetaPda(authority);
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram })
+      .signers([authority])
+      .rpc();
+    return authorityMeta;
+  }
+
+  // The treasury is a program-wide singleton: whichever test needs it first creates it.
+  async function ensureTreasury() {
+    if (!(await provider.connection.getAccountInfo(treasury))) {
+      await program.methods.initTreasury().accounts({ payer: provider.wallet.publicKey, treasury, systemProgram }).rpc();
+    }
+  }
+
+  // `initialize_market` options with every setting at its default, overridden by `overrides`.
+  function marketOptions(overrides = {}) {
+    return {
+      limits: { minBet: new anchor.BN(0), maxBetPerTicket: new anchor.BN(0), maxPool: new anchor.BN(0) },
+      dispute: { windowSecs: 0, bond: new anchor.BN(0) },
+      quorumThreshold: new anchor.BN(0),
+      oracle: null,
+      closeTs: new anchor.BN(0),
+      fixedStake: new anchor.BN(0),
+      noWinnerPolicy: { fees: {} },
+      referralBps: 0,
+      claimWindowSecs: 0,
+      unclaimedPolicy: { treasury: {} },
+      ...overrides,
+    };
+  }
+
+  function initializeMarket(authority, cycle, { title = `Market ${cycle}`, labels = ["YES", "NO"], feeBps = null, options = null } = {}) {
+    return program.methods
+      .initializeMarket(new anchor.BN(cycle), title, labels[0], labels[1], feeBps, options)
+      .accounts({
+        authority: authority.publicKey,
+        authorityMeta: authorityMetaPda(authority),
+        market: marketPda(authority, cycle),
+        systemProgram,
+      })
+      .signers([authority]);
+  }
+
+  async function openMarket(authority, cycle, settings) {
+    await initializeMarket(authority, cycle, settings).rpc();
+    return marketPda(authority, cycle);
+  }
+
+  async function createTicket(market, user, side, referrer = null) {
+    const ticket = ticketPda(market, user);
+    await program.methods
+      .createTicket(side, referrer && referrer.publicKey)
+      .accounts({
+        user: user.publicKey,
+        market,
+        ticket,
+        referrerVault: referrer ? pda(Buffer.from("referrer_vault"), referrer.publicKey.toBuffer()) : null,
+        systemProgram,
+      })
+      .signers([user])
+      .rpc();
+    return ticket;
+  }
+
+  function placeBet(market, user, lamports) {
+    return program.methods
+      .placeBet(new anchor.BN(lamports))
+      .accounts({ user: user.publicKey, market, ticket: ticketPda(market, user), systemProgram })
+      .signers([user])
+      .rpc();
+  }
+
+  // Opens `user`'s ticket on `side` and stakes `lamports` on it.
+  async function bet(market, user, side, lamports, referrer = null) {
+    const ticket = await createTicket(market, user, side, referrer);
+    await placeBet(market, user, lamports);
+    return ticket;
+  }
+
+  function castVote(market, resolver, side) {
+    return program.methods
+      .submitResolutionVote(side)
+      .accounts({ resolver: resolver.publicKey, market, vote: votePda(market) })
+      .signers([resolver])
+      .rpc();
+  }
+
+  function freeze(authority, market) {
+    return program.methods.freezeMarket().accounts({ authority: authority.publicKey, market }).signers([authority]).rpc();
+  }
+
+  function resolve(authority, market, winningSide) {
+    return program.methods
+      .resolveMarket(winningSide, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
+  }
 
   it("runs a market lifecycle", async () => {
     const authority = Keypair.generate();
@@ -45,7 +167,7 @@ describe("stream-bets-program", () => {
     );
 
     await program.methods
-      .initializeMarket("Hackathon Demo", "YES", "NO", null)
+      .initializeMarket(new anchor.BN(cycle), "Hackathon Demo", "YES", "NO", null, null)
       .accounts({
         authority: authority.publicKey,
         authorityMeta,
@@ -64,7 +186,7 @@ describe("stream-bets-program", () => {
     );
 
     await program.methods
-      .createTicket(0)
+      .createTicket(0, null)
       .accounts({
         user: bettor.publicKey,
         market,
@@ -92,27 +214,1235 @@ describe("stream-bets-program", () => {
       .rpc();
 
     await program.methods
-      .resolveMarket(0)
-      .accounts({ authority: authority.publicKey, market })
+      .resolveMarket(0, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
       .signers([authority])
       .rpc();
 
+    // Claims need no bettor signature: the provider wallet cranks it and the payout still goes to the bettor.
     await program.methods
       .claimWinnings()
       .accounts({ user: bettor.publicKey, market, ticket })
+      .rpc();
+
+    // The claim closes the ticket and returns its rent to the bettor.
+    assert.strictEqual(await provider.connection.getAccountInfo(ticket), null, "ticket should be closed by the claim");
+
+    const marketAccount = await program.account.betMarket.fetch(market);
+    assert.strictEqual(marketAccount.resolved, true, "market should remain resolved");
+  });
+
+  it("enforces per-market bet limits and the pool cap", async () => {
+    const authority = await fundedKeypair();
+    const [whale, minnow] = [await fundedKeypair(), await fundedKeypair()];
+    await initAuthorityMeta(authority);
+
+    const sol = (x) => new anchor.BN(x * LAMPORTS_PER_SOL);
+    await assert.rejects(
+      initializeMarket(authority, 0, { options: marketOptions({ limits: { minBet: sol(1), maxBetPerTicket: sol(0.5), maxPool: new anchor.BN(0) } }) }).rpc(),
+      /InvalidBetLimits/,
+    );
+    const limits = { minBet: sol(0.1), maxBetPerTicket: sol(0.5), maxPool: sol(0.8) };
+    const market = await openMarket(authority, 1, { options: marketOptions({ limits }) });
+
+    await createTicket(market, whale, 0);
+    await assert.rejects(placeBet(market, whale, 0.05 * LAMPORTS_PER_SOL), /BetBelowMinimum/);
+    await placeBet(market, whale, 0.3 * LAMPORTS_PER_SOL);
+    // The per-ticket cap applies to the ticket's cumulative stake, not to each bet.
+    await assert.rejects(placeBet(market, whale, 0.3 * LAMPORTS_PER_SOL), /BetAboveMaximum/);
+    await placeBet(market, whale, 0.2 * LAMPORTS_PER_SOL);
+
+    await createTicket(market, minnow, 1);
+    await assert.rejects(placeBet(market, minnow, 0.4 * LAMPORTS_PER_SOL), /PoolCapExceeded/);
+    await placeBet(market, minnow, 0.3 * LAMPORTS_PER_SOL);
+
+    const m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.poolYes.toString(), sol(0.5).toString());
+    assert.strictEqual(m.poolNo.toString(), sol(0.3).toString());
+  });
+
+  it("edits market text until the first bet", async () => {
+    const authority = await fundedKeypair();
+    const bettor = await fundedKeypair();
+    await initAuthorityMeta(authority);
+    const market = await openMarket(authority, 0, { title: "Nxet pack hit?" });
+    const text = (bytes) => Buffer.from(bytes).toString("utf8").replace(/\0+$/, "");
+    const update = (title, yes = "HIT", no = "MISS") =>
+      program.methods
+        .updateMarketMetadata(title, yes, no)
+        .accounts({ authority: authority.publicKey, market })
+        .signers([authority])
+        .rpc();
+
+    await update("Next pack hit? 🎴");
+    let m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(text(m.title), "Next pack hit? 🎴");
+    assert.strictEqual(text(m.labelYes), "HIT");
+    assert.strictEqual(text(m.labelNo), "MISS");
+
+    await assert.rejects(update("x".repeat(65)), /LabelTooLong/);
+    await assert.rejects(update("Next pack", "y".repeat(33)), /LabelTooLong/);
+    await assert.rejects(update("Next\0pack"), /InvalidLabelText/);
+
+    // Other keys cannot edit, and bettors must see the text they bet on.
+    const stranger = await fundedKeypair(1);
+    await assert.rejects(
+      program.methods
+        .updateMarketMetadata("Hijacked", "A", "B")
+        .accounts({ authority: stranger.publicKey, market })
+        .signers([stranger])
+        .rpc(),
+      /ConstraintSeeds|ConstraintHasOne/,
+    );
+    await bet(market, bettor, 0, 0.1 * LAMPORTS_PER_SOL);
+    await assert.rejects(update("Changed after betting"), /MarketHasBets/);
+    m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(text(m.title), "Next pack hit? 🎴");
+  });
+
+  it("keeps market text fixed while frozen", async () => {
+    const authority = await fundedKeypair();
+    await initAuthorityMeta(authority);
+    const market = await openMarket(authority, 0);
+    await freeze(authority, market);
+    await assert.rejects(
+      program.methods
+        .updateMarketMetadata("Frozen", "YES", "NO")
+        .accounts({ authority: authority.publicKey, market })
+        .signers([authority])
+        .rpc(),
+      /MarketFrozen/,
+    );
+  });
+
+  it("reopens a frozen market a limited number of times", async () => {
+    const authority = await fundedKeypair();
+    const bettor = await fundedKeypair();
+    await initAuthorityMeta(authority);
+    const market = await openMarket(authority, 0);
+    const unfreeze = () =>
+      program.methods
+        .unfreezeMarket()
+        .accounts({ authority: authority.publicKey, market })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+
+    await assert.rejects(unfreeze(), /MarketNotFrozen/);
+    await freeze(authority, market);
+    await createTicket(market, bettor, 0);
+    await assert.rejects(placeBet(market, bettor, 0.1 * LAMPORTS_PER_SOL), /MarketFrozen/);
+
+    // Every reopen is announced so bettors can see the pause happened.
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    for (let reopen = 1; reopen <= 3; reopen++) {
+      const sig = await unfreeze();
+      const tx = await provider.connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      const events = [...parser.parseLogs(tx.meta.logMessages)].filter((e) => e.name === "marketUnfrozenEvent");
+      assert.strictEqual(events.length, 1);
+      assert.ok(events[0].data.market.equals(market));
+      assert.ok(events[0].data.authority.equals(authority.publicKey));
+      assert.strictEqual(events[0].data.reopenCount, reopen);
+
+      const m = await program.account.betMarket.fetch(market);
+      assert.strictEqual(m.frozen, false);
+      assert.strictEqual(m.frozenAt.toNumber(), 0);
+      assert.strictEqual(m.reopenCount, reopen);
+      await placeBet(market, bettor, 0.1 * LAMPORTS_PER_SOL);
+      await freeze(authority, market);
+    }
+    await assert.rejects(unfreeze(), /ReopenLimitReached/);
+
+    await resolve(authority, market, 0);
+    await assert.rejects(unfreeze(), /MarketAlreadyResolved/);
+  });
+
+  it("records resolution evidence on the market and in its event", async () => {
+    const authority = await fundedKeypair();
+    const bettor = await fundedKeypair();
+    await initAuthorityMeta(authority);
+    const market = await openMarket(authority, 0);
+    await bet(market, bettor, 1, 0.1 * LAMPORTS_PER_SOL);
+    await freeze(authority, market);
+
+    const crypto = require("crypto");
+    const evidenceHash = [...crypto.createHash("sha256").update("frame-0412.png").digest()];
+    const resolveWith = (confidenceBps, source) =>
+      program.methods
+        .resolveMarket(1, evidenceHash, confidenceBps, source)
+        .accounts({ resolver: authority.publicKey, market })
+        .signers([authority])
+        .rpc({ commitment: "confirmed" });
+    await assert.rejects(resolveWith(10_001, { aiOcr: {} }), /InvalidConfidence/);
+    // Only the vote, arbiter, feed and timeout paths may claim those sources.
+    for (const source of [{ quorum: {} }, { arbiter: {} }, { oracle: {} }, { timeout: {} }]) {
+      await assert.rejects(resolveWith(9_000, source), /InvalidResolutionSource/);
+    }
+
+    const sig = await resolveWith(8_750, { aiOcr: {} });
+    const m = await program.account.betMarket.fetch(market);
+    assert.deepStrictEqual([...m.evidenceHash], evidenceHash);
+    assert.strictEqual(m.confidenceBps, 8_750);
+    assert.deepStrictEqual(m.resolutionSource, { aiOcr: {} });
+
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const tx = await provider.connection.getTransaction(sig, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+    const events = [...parser.parseLogs(tx.meta.logMessages)].filter((e) => e.name === "marketResolvedEvent");
+    assert.strictEqual(events.length, 1);
+    assert.deepStrictEqual([...events[0].data.evidenceHash], evidenceHash);
+    assert.strictEqual(events[0].data.confidenceBps, 8_750);
+    assert.deepStrictEqual(events[0].data.source, { aiOcr: {} });
+    assert.strictEqual(events[0].data.winningSide, 1);
+  });
+
+  it("holds claims while a resolution is disputed and applies the arbiter's ruling", async () => {
+    const authority = await fundedKeypair();
+    const [backer, doubter, bystander] = [await fundedKeypair(), await fundedKeypair(), await fundedKeypair()];
+    await ensureTreasury();
+    await initAuthorityMeta(authority);
+    const bond = 0.05 * LAMPORTS_PER_SOL;
+    const dispute = { windowSecs: 3_600, bond: new anchor.BN(bond) };
+
+    // `anchor test -- --features test-arbiter` builds the program around this fixture key.
+    const arbiter = Keypair.fromSecretKey(Uint8Array.from(require("./fixtures/arbiter.json")));
+    await requestAirdrop(provider.connection, arbiter.publicKey, 1);
+    const bondPda = (market, user) => pda(Buffer.from("bond"), market.toBuffer(), user.publicKey.toBuffer());
+    const challenge = (market, user) =>
+      program.methods
+        .challengeResolution()
+        .accounts({ challenger: user.publicKey, market, ticket: ticketPda(market, user), bond: bondPda(market, user), systemProgram })
+        .signers([user])
+        .rpc();
+    const arbitrate = (market, signer, overturn) =>
+      program.methods.arbitrateDispute(overturn).accounts({ arbiter: signer.publicKey, market }).signers([signer]).rpc();
+    const settleBond = (market, user) =>
+      program.methods
+        .settleDisputeBond()
+        .accounts({ challenger: user.publicKey, market, bond: bondPda(market, user) })
+        .rpc();
+
+    // Overturned: the winning side flips and the challenger's bond comes back in full.
+    const stake = 0.2 * LAMPORTS_PER_SOL;
+    const market = await openMarket(authority, 0, { options: marketOptions({ dispute }) });
+    await bet(market, backer, 0, stake);
+    const doubterTicket = await bet(market, doubter, 1, stake);
+    await createTicket(market, bystander, 0);
+    await freeze(authority, market);
+    await resolve(authority, market, 0);
+
+    await assert.rejects(challenge(market, bystander), /ZeroAmount/);
+    await assert.rejects(
+      program.methods.claimWinnings().accounts({ user: backer.publicKey, market, ticket: ticketPda(market, backer) }).rpc(),
+      /DisputeWindowOpen/,
+    );
+    const before = await provider.connection.getBalance(doubter.publicKey);
+    await challenge(market, doubter);
+    let m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.disputeState, 1);
+    assert.strictEqual(m.challengeCount, 1);
+    const posted = await program.account.disputeBond.fetch(bondPda(market, doubter));
+    assert.strictEqual(posted.amount.toNumber(), bond);
+
+    await assert.rejects(settleBond(market, doubter), /DisputeUnsettled/);
+    await assert.rejects(program.methods.expireDispute().accounts({ market }).rpc(), /ArbitrationOpen/);
+    // Only the arbiter rules, not the market authority.
+    await assert.rejects(arbitrate(market, authority, true), /Unauthorized/);
+    await assert.rejects(
+      program.methods.claimWinnings().accounts({ user: doubter.publicKey, market, ticket: doubterTicket }).rpc(),
+      /DisputePending/,
+    );
+
+    await arbitrate(market, arbiter, true);
+    m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.disputeState, 3);
+    assert.strictEqual(m.winningSide, 1);
+    assert.deepStrictEqual(m.resolutionSource, { arbiter: {} });
+    await assert.rejects(arbitrate(market, arbiter, false), /NotChallenged/);
+    await settleBond(market, doubter);
+    await program.methods.claimWinnings().accounts({ user: doubter.publicKey, market, ticket: doubterTicket }).rpc();
+    const after = await provider.connection.getBalance(doubter.publicKey);
+    assert.ok(after - before > stake, "bond and winnings returned to the challenger");
+
+    // Upheld: the result stands and the bond is slashed into the market's fees.
+    const upheld = await openMarket(authority, 1, { options: marketOptions({ dispute }) });
+    await bet(upheld, backer, 0, stake);
+    await bet(upheld, doubter, 1, stake);
+    await freeze(authority, upheld);
+    await resolve(authority, upheld, 0);
+    await challenge(upheld, doubter);
+    await arbitrate(upheld, arbiter, false);
+    await settleBond(upheld, doubter);
+    m = await program.account.betMarket.fetch(upheld);
+    assert.strictEqual(m.disputeState, 2);
+    assert.strictEqual(m.winningSide, 0);
+    assert.strictEqual(m.feesAccrued.toNumber(), bond);
+    assert.strictEqual(await provider.connection.getAccountInfo(bondPda(upheld, doubter)), null, "bond rent returned");
+  });
+
+  it("resolves a high-value market by a K-of-N resolver vote", async () => {
+    const authority = await fundedKeypair();
+    const [backer, doubter] = [await fundedKeypair(), await fundedKeypair()];
+    await initAuthorityMeta(authority);
+    const stake = 0.2 * LAMPORTS_PER_SOL;
+    const market = await openMarket(authority, 0, {
+      options: marketOptions({ quorumThreshold: new anchor.BN(stake) }),
+    });
+    await bet(market, backer, 0, stake);
+    await bet(market, doubter, 1, stake);
+    await freeze(authority, market);
+    await assert.rejects(resolve(authority, market, 0), /QuorumRequired/);
+
+    const resolvers = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    const keys = resolvers.map((r) => r.publicKey);
+    const openVote = (list, quorum) =>
+      program.methods
+        .initResolutionVote(list, quorum, 3_600)
+        .accounts({ authority: authority.publicKey, market, vote: votePda(market), systemProgram })
+        .signers([authority])
+        .rpc();
+    await assert.rejects(openVote(keys, 1), /InvalidQuorum/); // below MIN_VOTE_QUORUM
+    await assert.rejects(openVote([...keys, Keypair.generate().publicKey], 2), /InvalidQuorum/); // 2 of 4 is no majority
+    await assert.rejects(openVote([keys[0], keys[1], authority.publicKey], 2), /InvalidQuorum/);
+    await assert.rejects(openVote([keys[0], keys[0], keys[1]], 2), /InvalidQuorum/);
+    await openVote(keys, 2);
+
+    await assert.rejects(castVote(market, Keypair.generate(), 0), /Unauthorized/);
+    await castVote(market, resolvers[0], 0);
+    await assert.rejects(castVote(market, resolvers[0], 1), /AlreadyVoted/);
+    let m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.resolved, false, "one vote is short of quorum");
+
+    await castVote(market, resolvers[1], 0);
+    m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.resolved, true);
+    assert.strictEqual(m.winningSide, 0);
+    assert.deepStrictEqual(m.resolutionSource, { quorum: {} });
+    await assert.rejects(castVote(market, resolvers[2], 1), /MarketAlreadyResolved/);
+  });
+
+  it("voids a resolver vote that deadlocks or times out", async () => {
+    const authority = await fundedKeypair();
+    const bettor = await fundedKeypair();
+    await initAuthorityMeta(authority);
+    const stake = 0.1 * LAMPORTS_PER_SOL;
+    const openVote = (market, resolvers, quorum, timeoutSecs) =>
+      program.methods
+        .initResolutionVote(resolvers.map((r) => r.publicKey), quorum, timeoutSecs)
+        .accounts({ authority: authority.publicKey, market, vote: votePda(market), systemProgram })
+        .signers([authority])
+        .rpc();
+
+    // 3 of 4 split two-two: neither side can reach quorum any more.
+    const split = await openMarket(authority, 0);
+    await bet(split, bettor, 0, stake);
+    await freeze(authority, split);
+    const four = [Keypair.generate(), Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    await openVote(split, four, 3, 3_600);
+    for (const [i, side] of [0, 0, 1].entries()) await castVote(split, four[i], side);
+    let m = await program.account.betMarket.fetch(split);
+    assert.strictEqual(m.resolved, false, "the last vote can still decide");
+    await castVote(split, four[3], 1);
+    m = await program.account.betMarket.fetch(split);
+    assert.strictEqual(m.winningSide, 2, "deadlock voids the market");
+    assert.deepStrictEqual(m.resolutionSource, { quorum: {} });
+
+    // Nobody votes before the timeout: anyone may void it, and the stake is refunded in full.
+    const quiet = await openMarket(authority, 1);
+    const ticket = await bet(quiet, bettor, 1, stake);
+    await freeze(authority, quiet);
+    const three = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    await openVote(quiet, three, 2, 2);
+    const expire = () => program.methods.expireResolutionVote().accounts({ market: quiet, vote: votePda(quiet) }).rpc();
+    await assert.rejects(expire(), /VoteStillOpen/);
+    // The seven-day stale-market void is covered by the clock-warped Rust tests.
+    await assert.rejects(program.methods.voidStaleMarket().accounts({ market: quiet }).rpc(), /ResolveTooEarly/);
+    await new Promise((r) => setTimeout(r, 3000));
+    await assert.rejects(castVote(quiet, three[0], 1), /VoteExpired/);
+    await expire();
+    m = await program.account.betMarket.fetch(quiet);
+    assert.strictEqual(m.resolved, true);
+    assert.strictEqual(m.winningSide, 2);
+
+    const before = await provider.connection.getBalance(bettor.publicKey);
+    await program.methods.claimWinnings().accounts({ user: bettor.publicKey, market: quiet, ticket }).rpc();
+    assert.ok((await provider.connection.getBalance(bettor.publicKey)) - before >= stake, "stake refunded");
+  });
+
+  it("resolves an oracle market from a mock feed", async () => {
+    const feedProgram = anchor.workspace.MockFeed;
+    const authority = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    // Raw 24-byte feed account owned by mock-feed (layout: punt_program::feed).
+    const feed = Keypair.generate();
+    const feedLen = 24;
+    const createFeed = SystemProgram.createAccount({
+      fromPubkey: authority.publicKey,
+      newAccountPubkey: feed.publicKey,
+      lamports: await provider.connection.getMinimumBalanceForRentExemption(feedLen),
+      space: feedLen,
+      programId: feedProgram.programId,
+    });
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(createFeed), [authority, feed]);
+
+    const resolveTs = Math.floor(Date.now() / 1000) + 3;
+    const [market] = PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(0)],
+      program.programId,
+    );
+    const options = {
+      limits: { minBet: new anchor.BN(0), maxBetPerTicket: new anchor.BN(0), maxPool: new anchor.BN(0) },
+      dispute: { windowSecs: 0, bond: new anchor.BN(0) },
+      quorumThreshold: new anchor.BN(0),
+      oracle: { feed: feed.publicKey, resolveTs: new anchor.BN(resolveTs), threshold: new anchor.BN(150) },
+      closeTs: new anchor.BN(0),
+      fixedStake: new anchor.BN(0),
+      noWinnerPolicy: { fees: {} },
+      referralBps: 0,
+      claimWindowSecs: 0,
+      unclaimedPolicy: { treasury: {} },
+    };
+    await program.methods
+      .initializeMarket(new anchor.BN(0), "SOL above 150?", "ABOVE", "BELOW", null, options)
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    await new Promise((r) => setTimeout(r, 4000));
+    await feedProgram.methods
+      .setValue(new anchor.BN(151), new anchor.BN(resolveTs + 1))
+      .accounts({ feed: feed.publicKey })
+      .rpc();
+    await program.methods.resolveFromFeed().accounts({ market, feed: feed.publicKey }).rpc();
+
+    const marketAccount = await program.account.betMarket.fetch(market);
+    assert.strictEqual(marketAccount.resolved, true);
+    assert.strictEqual(marketAccount.winningSide, 0, "151 > 150 resolves YES");
+  });
+
+  it("creates consecutive markets from a template", async () => {
+    const authority = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    const [template] = PublicKey.findProgramAddressSync(
+      [Buffer.from("template"), authorityMeta.toBuffer(), templateSeed(7)],
+      program.programId,
+    );
+    const limits = { minBet: new anchor.BN(1000), maxBetPerTicket: new anchor.BN(0), maxPool: new anchor.BN(0) };
+    await program.methods
+      .createMarketTemplate(7, "Next pack has a hit?", "HIT", "MISS", 50, limits, 600)
+      .accounts({ authority: authority.publicKey, authorityMeta, template, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    for (const cycle of [0, 1]) {
+      const [market] = PublicKey.findProgramAddressSync(
+        [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(cycle)],
+        program.programId,
+      );
+      await program.methods
+        .initializeMarketFromTemplate(new anchor.BN(cycle))
+        .accounts({ authority: authority.publicKey, authorityMeta, template, market, systemProgram: SystemProgram.programId })
+        .signers([authority])
+        .rpc();
+      const m = await program.account.betMarket.fetch(market);
+      assert.strictEqual(m.cycle, cycle);
+      assert.strictEqual(m.feeBps, 50);
+      assert.strictEqual(m.minBet.toNumber(), 1000);
+      assert.strictEqual(Buffer.from(m.labelYes).toString("utf8").replace(/\0+$/, ""), "HIT");
+      assert.ok(m.closeTs.toNumber() > Math.floor(Date.now() / 1000), "betting window starts at creation");
+    }
+
+    const meta = await program.account.authorityMeta.fetch(authorityMeta);
+    assert.strictEqual(meta.nextCycle.toNumber(), 2);
+  });
+
+  it("keeps several markets open under client-chosen ids", async () => {
+    const authority = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    // Two open markets created in one transaction; ids may skip ahead.
+    const tx = new anchor.web3.Transaction();
+    for (const cycle of [42, 1_000_000_000_000]) {
+      const [market] = PublicKey.findProgramAddressSync(
+        [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(cycle)],
+        program.programId,
+      );
+      tx.add(
+        await program.methods
+          .initializeMarket(new anchor.BN(cycle), `Market ${cycle}`, "YES", "NO", null, null)
+          .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+          .instruction(),
+      );
+    }
+    await provider.sendAndConfirm(tx, [authority]);
+
+    const meta = await program.account.authorityMeta.fetch(authorityMeta);
+    assert.strictEqual(meta.nextCycle.toNumber(), 1_000_000_000_001, "next_cycle tracks the highest id");
+
+    // Any id without an open market is accepted, in any order; an open one is not.
+    const marketAt = (cycle) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(cycle)],
+        program.programId,
+      )[0];
+    const open = (cycle, title) =>
+      program.methods
+        .initializeMarket(new anchor.BN(cycle), title, "YES", "NO", null, null)
+        .accounts({ authority: authority.publicKey, authorityMeta, market: marketAt(cycle), systemProgram: SystemProgram.programId })
+        .signers([authority])
+        .rpc();
+    await open(7, "Skipped");
+    await assert.rejects(open(42, "Taken"), /already in use|0x0/);
+    const markets = await Promise.all([42, 1_000_000_000_000, 7].map((cycle) => program.account.betMarket.fetch(marketAt(cycle))));
+    assert.deepStrictEqual(markets.map((m) => m.serial.toNumber()), [1, 2, 3], "serials count creations");
+  });
+
+  it("accepts exactly one fixed stake per ticket", async () => {
+    const authority = Keypair.generate();
+    const bettor = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+    await requestAirdrop(provider.connection, bettor.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const [market] = PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(0)],
+      program.programId,
+    );
+    const [ticket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ticket"), market.toBuffer(), bettor.publicKey.toBuffer()],
+      program.programId,
+    );
+    const stake = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+    const options = {
+      limits: { minBet: new anchor.BN(0), maxBetPerTicket: new anchor.BN(0), maxPool: new anchor.BN(0) },
+      dispute: { windowSecs: 0, bond: new anchor.BN(0) },
+      quorumThreshold: new anchor.BN(0),
+      oracle: null,
+      closeTs: new anchor.BN(0),
+      fixedStake: stake,
+      noWinnerPolicy: { fees: {} },
+      referralBps: 0,
+      claimWindowSecs: 0,
+      unclaimedPolicy: { treasury: {} },
+    };
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .initializeMarket(new anchor.BN(0), "Predict the Pull", "HIT", "MISS", null, options)
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .createTicket(0, null)
+      .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
       .signers([bettor])
       .rpc();
 
-    const ticketAccount = await program.account.betTicket.fetch(ticket);
-    assert.strictEqual(ticketAccount.claimed, true, "ticket should be marked claimed");
+    const bet = (amount) =>
+      program.methods
+        .placeBet(amount)
+        .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([bettor])
+        .rpc();
+    await assert.rejects(bet(stake.muln(2)), /FixedStakeMismatch/);
+    await bet(stake);
+    await assert.rejects(bet(stake), /AlreadyStaked/);
 
+    const m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.bettorsYes, 1);
+    assert.strictEqual(m.poolYes.toString(), stake.toString());
+  });
+
+  it("draws a prize winner from a committed seed", async () => {
+    const crypto = require("crypto");
+    const authority = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const [market] = PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(0)],
+      program.programId,
+    );
     await program.methods
-      .closeTicket()
-      .accounts({ user: bettor.publicKey, market, ticket })
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .initializeMarket(new anchor.BN(0), "Pack has a hit?", "HIT", "MISS", null, null)
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    const seed = crypto.randomBytes(32);
+    const seedHash = crypto.createHash("sha256").update(seed).digest();
+    await program.methods
+      .commitPrizeSeed([...seedHash], { perTicket: {} })
+      .accounts({ authority: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
+
+    const tickets = [];
+    for (const side of [0, 0, 1]) {
+      const bettor = Keypair.generate();
+      await requestAirdrop(provider.connection, bettor.publicKey, 1);
+      const [ticket] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ticket"), market.toBuffer(), bettor.publicKey.toBuffer()],
+        program.programId,
+      );
+      await program.methods
+        .createTicket(side, null)
+        .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([bettor])
+        .rpc();
+      await program.methods
+        .placeBet(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+        .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([bettor])
+        .rpc();
+      tickets.push({ ticket, user: bettor.publicKey, side });
+    }
+
+    await program.methods.freezeMarket().accounts({ authority: authority.publicKey, market }).signers([authority]).rpc();
+    await program.methods
+      .resolveMarket(0, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
+
+    const winners = tickets.filter((t) => t.side === 0);
+    // Pages follow the tickets' entry order, which is the order their first bets landed in.
+    const remaining = winners.map((t) => ({ pubkey: t.ticket, isSigner: false, isWritable: false }));
+    await assert.rejects(
+      program.methods.drawPrizeWinner([...crypto.randomBytes(32)]).accounts({ market }).remainingAccounts(remaining).rpc(),
+      /PrizeSeedMismatch/,
+    );
+    // Anyone holding the seed walks the winners, one ticket per page here.
+    await program.methods.drawPrizeWinner([...seed]).accounts({ market }).remainingAccounts(remaining.slice(0, 1)).rpc();
+    let m = await program.account.betMarket.fetch(market);
+    assert.ok(m.prizeWinner.equals(PublicKey.default), "no winner before every ticket is walked");
+    await assert.rejects(
+      program.methods.drawPrizeWinner([...seed]).accounts({ market }).remainingAccounts(remaining).rpc(),
+      /PrizeEntrantsInvalid/,
+    );
+    await program.methods.drawPrizeWinner([...seed]).accounts({ market }).remainingAccounts(remaining.slice(1)).rpc();
+
+    m = await program.account.betMarket.fetch(market);
+    assert.ok(winners.some((t) => t.user.equals(m.prizeWinner)), "winner holds a winning ticket");
+  });
+
+  it("refunds every stake on a void resolution", async () => {
+    const authority = Keypair.generate();
+    const bettor = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+    await requestAirdrop(provider.connection, bettor.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const [market] = PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(0)],
+      program.programId,
+    );
+    const [ticket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ticket"), market.toBuffer(), bettor.publicKey.toBuffer()],
+      program.programId,
+    );
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .initializeMarket(new anchor.BN(0), "Stream crashed?", "YES", "NO", null, null)
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .createTicket(1, null)
+      .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+      .signers([bettor])
+      .rpc();
+    const stake = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
+    await program.methods
+      .placeBet(stake)
+      .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
       .signers([bettor])
       .rpc();
+    await program.methods.freezeMarket().accounts({ authority: authority.publicKey, market }).signers([authority]).rpc();
+    await program.methods
+      .resolveMarket(2, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
 
-    const marketAccount = await program.account.betMarket.fetch(market);
-    assert.strictEqual(marketAccount.resolved, true, "market should remain resolved");
+    const before = await provider.connection.getBalance(bettor.publicKey);
+    await program.methods.claimWinnings().accounts({ user: bettor.publicKey, market, ticket }).rpc();
+    const after = await provider.connection.getBalance(bettor.publicKey);
+    assert.ok(after - before >= stake.toNumber(), "full stake (plus ticket rent) returned");
+
+    const m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.feesAccrued.toNumber(), 0, "void resolutions charge no fee");
+  });
+
+  it("rolls a no-winner pool over into the next market", async () => {
+    const authority = Keypair.generate();
+    const bettor = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+    await requestAirdrop(provider.connection, bettor.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const [first, next] = [0, 1].map(
+      (cycle) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(cycle)],
+          program.programId,
+        )[0],
+    );
+    const [ticket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ticket"), first.toBuffer(), bettor.publicKey.toBuffer()],
+      program.programId,
+    );
+    const options = {
+      limits: { minBet: new anchor.BN(0), maxBetPerTicket: new anchor.BN(0), maxPool: new anchor.BN(0) },
+      dispute: { windowSecs: 0, bond: new anchor.BN(0) },
+      quorumThreshold: new anchor.BN(0),
+      oracle: null,
+      closeTs: new anchor.BN(0),
+      fixedStake: new anchor.BN(0),
+      noWinnerPolicy: { rollover: {} },
+      referralBps: 0,
+      claimWindowSecs: 0,
+      unclaimedPolicy: { treasury: {} },
+    };
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    for (const [cycle, market] of [[0, first], [1, next]]) {
+      await program.methods
+        .initializeMarket(new anchor.BN(cycle), `Round ${cycle}`, "YES", "NO", null, options)
+        .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+        .signers([authority])
+        .rpc();
+    }
+    await program.methods
+      .createTicket(1, null)
+      .accounts({ user: bettor.publicKey, market: first, ticket, systemProgram: SystemProgram.programId })
+      .signers([bettor])
+      .rpc();
+    const stake = new anchor.BN(0.2 * LAMPORTS_PER_SOL);
+    await program.methods
+      .placeBet(stake)
+      .accounts({ user: bettor.publicKey, market: first, ticket, systemProgram: SystemProgram.programId })
+      .signers([bettor])
+      .rpc();
+    await program.methods.freezeMarket().accounts({ authority: authority.publicKey, market: first }).signers([authority]).rpc();
+    await program.methods
+      .resolveMarket(0, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market: first })
+      .signers([authority])
+      .rpc();
+
+    let m = await program.account.betMarket.fetch(first);
+    assert.strictEqual(m.rolloverPending.toNumber(), stake.toNumber());
+    assert.strictEqual(m.feesAccrued.toNumber(), 0, "rolled pools are not taken as fees");
+
+    await program.methods
+      .rollOverPool()
+      .accounts({ authority: authority.publicKey, market: first, destination: next })
+      .signers([authority])
+      .rpc();
+    m = await program.account.betMarket.fetch(first);
+    assert.strictEqual(m.rolloverPending.toNumber(), 0);
+    const n = await program.account.betMarket.fetch(next);
+    assert.strictEqual(n.bonusPool.toNumber(), stake.toNumber(), "winners of the next market share the pool");
+  });
+
+  it("pays a referrer its share of a referred ticket's fee", async () => {
+    const authority = Keypair.generate();
+    const referrer = Keypair.generate();
+    const [backer, doubter] = [Keypair.generate(), Keypair.generate()];
+    for (const kp of [authority, referrer, backer, doubter]) {
+      await requestAirdrop(provider.connection, kp.publicKey, 2);
+    }
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const [market] = PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(0)],
+      program.programId,
+    );
+    const [referrerVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("referrer_vault"), referrer.publicKey.toBuffer()],
+      program.programId,
+    );
+    const ticketOf = (kp) =>
+      PublicKey.findProgramAddressSync([Buffer.from("ticket"), market.toBuffer(), kp.publicKey.toBuffer()], program.programId)[0];
+    const options = {
+      limits: { minBet: new anchor.BN(0), maxBetPerTicket: new anchor.BN(0), maxPool: new anchor.BN(0) },
+      dispute: { windowSecs: 0, bond: new anchor.BN(0) },
+      quorumThreshold: new anchor.BN(0),
+      oracle: null,
+      closeTs: new anchor.BN(0),
+      fixedStake: new anchor.BN(0),
+      noWinnerPolicy: { fees: {} },
+      referralBps: 5_000,
+      claimWindowSecs: 0,
+      unclaimedPolicy: { treasury: {} },
+    };
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .initializeMarket(new anchor.BN(0), "Referred", "YES", "NO", null, options)
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .initReferrerVault()
+      .accounts({ referrer: referrer.publicKey, referrerVault, systemProgram: SystemProgram.programId })
+      .signers([referrer])
+      .rpc();
+
+    const stake = new anchor.BN(0.5 * LAMPORTS_PER_SOL);
+    for (const [kp, side, ref] of [[backer, 0, referrer.publicKey], [doubter, 1, null]]) {
+      const ticket = ticketOf(kp);
+      await program.methods
+        .createTicket(side, ref)
+        .accounts({
+          user: kp.publicKey,
+          market,
+          ticket,
+          referrerVault: ref ? referrerVault : null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([kp])
+        .rpc();
+      await program.methods
+        .placeBet(stake)
+        .accounts({ user: kp.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([kp])
+        .rpc();
+    }
+    await program.methods.freezeMarket().accounts({ authority: authority.publicKey, market }).signers([authority]).rpc();
+    await program.methods
+      .resolveMarket(0, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .claimWinnings()
+      .accounts({ user: backer.publicKey, market, ticket: ticketOf(backer), referrerVault })
+      .rpc();
+
+    // 0.5 SOL profit at 690 bps total fee = 34_500_000; the referrer takes half of it.
+    const vault = await program.account.referrerVault.fetch(referrerVault);
+    assert.strictEqual(vault.accrued.toNumber(), 17_250_000);
+    const m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.feesAccrued.toNumber(), 17_250_000, "authority and host split the remainder");
+
+    await program.methods
+      .withdrawReferralRewards()
+      .accounts({ referrer: referrer.publicKey, referrerVault })
+      .signers([referrer])
+      .rpc();
+    const drained = await program.account.referrerVault.fetch(referrerVault);
+    assert.strictEqual(drained.accrued.toNumber(), 0);
+    assert.strictEqual(drained.totalEarned.toNumber(), 17_250_000);
+  });
+
+  it("routes the host fee share into the treasury", async () => {
+    const authority = Keypair.generate();
+    const [backer, doubter] = [Keypair.generate(), Keypair.generate()];
+    for (const kp of [authority, backer, doubter]) {
+      await requestAirdrop(provider.connection, kp.publicKey, 2);
+    }
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const [market] = PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(0)],
+      program.programId,
+    );
+    const [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+    const ticketOf = (kp) =>
+      PublicKey.findProgramAddressSync([Buffer.from("ticket"), market.toBuffer(), kp.publicKey.toBuffer()], program.programId)[0];
+
+    // The dispute test may already have created the program-wide treasury.
+    await ensureTreasury();
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .initializeMarket(new anchor.BN(0), "Treasury", "YES", "NO", null, null)
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    const stake = new anchor.BN(0.5 * LAMPORTS_PER_SOL);
+    for (const [kp, side] of [[backer, 0], [doubter, 1]]) {
+      const ticket = ticketOf(kp);
+      await program.methods
+        .createTicket(side, null)
+        .accounts({ user: kp.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([kp])
+        .rpc();
+      await program.methods
+        .placeBet(stake)
+        .accounts({ user: kp.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([kp])
+        .rpc();
+    }
+    await program.methods.freezeMarket().accounts({ authority: authority.publicKey, market }).signers([authority]).rpc();
+    await program.methods
+      .resolveMarket(0, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
+    await program.methods.claimWinnings().accounts({ user: backer.publicKey, market, ticket: ticketOf(backer) }).rpc();
+    await program.methods.closeTicket().accounts({ user: doubter.publicKey, market, ticket: ticketOf(doubter) }).rpc();
+
+    // No host wallet involved: fees split 20 / 670 bps between the authority and the treasury.
+    const collectedBefore = (await program.account.treasury.fetch(treasury)).collected.toNumber();
+    await program.methods
+      .withdrawFees()
+      .accounts({ caller: authority.publicKey, authority: authority.publicKey, market, treasury })
+      .signers([authority])
+      .rpc();
+    const m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.treasuryPaid.toNumber(), 33_500_000);
+    const t = await program.account.treasury.fetch(treasury);
+    assert.strictEqual(t.collected.toNumber() - collectedBefore, 33_500_000);
+
+    // Every payout is claimed, so anyone may close; the rent (minus the crank tip) goes to the authority.
+    const before = await provider.connection.getBalance(authority.publicKey);
+    await program.methods
+      .closeMarket()
+      .accounts({ caller: provider.wallet.publicKey, authority: authority.publicKey, market, treasury })
+      .rpc();
+    assert.ok((await provider.connection.getBalance(authority.publicKey)) > before, "authority receives the rent");
+  });
+
+  it("lets anyone crank fee withdrawal and close once every payout is claimed", async () => {
+    const CRANK_TIP_LAMPORTS = 10_000;
+    const authority = await fundedKeypair();
+    const [backer, doubter, cranker] = [await fundedKeypair(), await fundedKeypair(), await fundedKeypair(0.1)];
+    const recipients = [await fundedKeypair(0.1), await fundedKeypair(0.1)];
+    await ensureTreasury();
+    await initAuthorityMeta(authority);
+    const market = await openMarket(authority, 0, { feeBps: 500 });
+    const feeSchedule = pda(Buffer.from("fee_schedule"), market.toBuffer());
+    await program.methods
+      .setFeeSchedule(recipients.map((r) => r.publicKey), [6_000, 4_000], 0)
+      .accounts({ authority: authority.publicKey, market, feeSchedule, systemProgram })
+      .signers([authority])
+      .rpc();
+    const backerTicket = await bet(market, backer, 0, 0.5 * LAMPORTS_PER_SOL);
+    await bet(market, doubter, 1, 0.25 * LAMPORTS_PER_SOL);
+    await freeze(authority, market);
+
+    // The provider wallet pays the transaction fees, so the cranker's balance moves only by the tip.
+    const accounts = { caller: cranker.publicKey, authority: authority.publicKey, market, treasury, feeSchedule };
+    const remaining = recipients.map((r) => ({ pubkey: r.publicKey, isWritable: true, isSigner: false }));
+    const crankWithdraw = () =>
+      program.methods.withdrawFees().accounts(accounts).remainingAccounts(remaining).signers([cranker]).rpc();
+    const crankClose = () =>
+      program.methods.closeMarket().accounts(accounts).remainingAccounts(remaining).signers([cranker]).rpc();
+    await assert.rejects(crankWithdraw(), /MarketNotResolved/);
+    await resolve(authority, market, 0);
+    await assert.rejects(crankWithdraw(), /ClaimsOutstanding/);
+    await assert.rejects(crankClose(), /ClaimsOutstanding/);
+    await program.methods.claimWinnings().accounts({ user: backer.publicKey, market, ticket: backerTicket }).rpc();
+
+    const balance = (k) => provider.connection.getBalance(k.publicKey);
+    const m = await program.account.betMarket.fetch(market);
+    const fees = m.feesAccrued.toNumber();
+    const authorityShare = Math.floor((fees * m.feeBps) / (m.feeBps + m.hostFeeBps));
+    const authorityBefore = await balance(authority);
+    const recipientsBefore = await Promise.all(recipients.map(balance));
+    await crankWithdraw();
+    const recipientsAfter = await Promise.all(recipients.map(balance));
+    const paid = recipientsAfter.map((b, i) => b - recipientsBefore[i]);
+    assert.strictEqual(paid[0] + paid[1], authorityShare, "the schedule receives the whole authority share");
+    assert.strictEqual(paid[1], Math.floor((authorityShare * 4_000) / 10_000));
+
+    // The tip comes out of the rent; the authority gets the rest of it back, schedule rent included.
+    const rent =
+      (await provider.connection.getBalance(market)) + (await provider.connection.getBalance(feeSchedule));
+    const crankerBefore = await balance(cranker);
+    await crankClose();
+    assert.strictEqual((await balance(cranker)) - crankerBefore, CRANK_TIP_LAMPORTS);
+    assert.strictEqual((await balance(authority)) - authorityBefore, rent - CRANK_TIP_LAMPORTS);
+    assert.strictEqual(await provider.connection.getAccountInfo(market), null);
+    assert.strictEqual(await provider.connection.getAccountInfo(feeSchedule), null);
+  });
+
+  it("splits the authority's fees across a fee schedule", async () => {
+    const authority = await fundedKeypair();
+    const [backer, doubter] = [await fundedKeypair(), await fundedKeypair()];
+    const recipients = [await fundedKeypair(0.1), await fundedKeypair(0.1), await fundedKeypair(0.1)];
+    await ensureTreasury();
+    await initAuthorityMeta(authority);
+    const market = await openMarket(authority, 0, { feeBps: 500 });
+    const feeSchedule = pda(Buffer.from("fee_schedule"), market.toBuffer());
+    const setSchedule = (weights, remainderIndex = 1) =>
+      program.methods
+        .setFeeSchedule(recipients.map((r) => r.publicKey), weights, remainderIndex)
+        .accounts({ authority: authority.publicKey, market, feeSchedule, systemProgram })
+        .signers([authority])
+        .rpc();
+    await assert.rejects(setSchedule([3_333, 3_333, 3_333]), /InvalidFeeSchedule/); // must sum to 10000
+    await assert.rejects(setSchedule([3_333, 3_333, 3_334], 3), /InvalidFeeSchedule/);
+    const weights = [3_333, 3_333, 3_334];
+    await setSchedule(weights);
+
+    // An odd losing stake so the weighted split leaves a rounding remainder.
+    const backerTicket = await bet(market, backer, 0, 0.5 * LAMPORTS_PER_SOL);
+    const doubterTicket = await bet(market, doubter, 1, 123_456_789);
+    await assert.rejects(setSchedule(weights), /MarketHasBets/);
+    await freeze(authority, market);
+    await resolve(authority, market, 0);
+    await program.methods.claimWinnings().accounts({ user: backer.publicKey, market, ticket: backerTicket }).rpc();
+    await program.methods.closeTicket().accounts({ user: doubter.publicKey, market, ticket: doubterTicket }).rpc();
+
+    const withdraw = (schedule, accounts) =>
+      program.methods
+        .withdrawFees()
+        .accounts({ caller: authority.publicKey, authority: authority.publicKey, market, treasury, feeSchedule: schedule })
+        .remainingAccounts(accounts.map((r) => ({ pubkey: r.publicKey, isWritable: true, isSigner: false })))
+        .signers([authority])
+        .rpc();
+    await assert.rejects(withdraw(null, []), /FeeScheduleMismatch/);
+    await assert.rejects(withdraw(feeSchedule, recipients.slice(0, 2)), /FeeScheduleMismatch/);
+    await assert.rejects(withdraw(feeSchedule, [recipients[1], recipients[0], recipients[2]]), /FeeScheduleMismatch/);
+
+    const m = await program.account.betMarket.fetch(market);
+    const fees = BigInt(m.feesAccrued.toString());
+    const authorityShare = (fees * BigInt(m.feeBps)) / BigInt(m.feeBps + m.hostFeeBps);
+    const shares = weights.map((w) => (authorityShare * BigInt(w)) / BigInt(10_000));
+    const remainder = authorityShare - shares.reduce((a, b) => a + b);
+    assert.ok(remainder > BigInt(0), "the split should not be exact");
+    shares[1] += remainder;
+
+    const balances = () => Promise.all(recipients.map((r) => provider.connection.getBalance(r.publicKey)));
+    const authorityBefore = await provider.connection.getBalance(authority.publicKey);
+    const before = await balances();
+    await withdraw(feeSchedule, recipients);
+    const after = await balances();
+    recipients.forEach((_, i) => assert.strictEqual(BigInt(after[i] - before[i]), shares[i], `recipient ${i}`));
+    // With a schedule the authority itself receives none of the share.
+    assert.ok((await provider.connection.getBalance(authority.publicKey)) <= authorityBefore);
+  });
+
+  it("keeps claims open for at least the minimum claim window", async () => {
+    const authority = Keypair.generate();
+    const bettor = Keypair.generate();
+    await requestAirdrop(provider.connection, authority.publicKey, 2);
+    await requestAirdrop(provider.connection, bettor.publicKey, 2);
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const marketAt = (cycle) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(cycle)],
+        program.programId,
+      )[0];
+    const [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+    const options = (claimWindowSecs) => ({
+      limits: { minBet: new anchor.BN(0), maxBetPerTicket: new anchor.BN(0), maxPool: new anchor.BN(0) },
+      dispute: { windowSecs: 0, bond: new anchor.BN(0) },
+      quorumThreshold: new anchor.BN(0),
+      oracle: null,
+      closeTs: new anchor.BN(0),
+      fixedStake: new anchor.BN(0),
+      noWinnerPolicy: { fees: {} },
+      referralBps: 0,
+      claimWindowSecs,
+      unclaimedPolicy: { authority: {} },
+    });
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+    // A one-second window would let the authority sweep every payout to itself right away.
+    try {
+      await program.methods
+        .initializeMarket(new anchor.BN(0), "Blink", "YES", "NO", null, options(1))
+        .accounts({ authority: authority.publicKey, authorityMeta, market: marketAt(0), systemProgram: SystemProgram.programId })
+        .signers([authority])
+        .rpc();
+      assert.fail("a claim window below the minimum must be rejected");
+    } catch (e) {
+      assert.match(String(e), /InvalidClaimWindow/);
+    }
+
+    const market = marketAt(1);
+    const [ticket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ticket"), market.toBuffer(), bettor.publicKey.toBuffer()],
+      program.programId,
+    );
+    await program.methods
+      .initializeMarket(new anchor.BN(1), "Week", "YES", "NO", null, options(7 * 86_400))
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .createTicket(0, null)
+      .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+      .signers([bettor])
+      .rpc();
+    await program.methods
+      .placeBet(new anchor.BN(0.2 * LAMPORTS_PER_SOL))
+      .accounts({ user: bettor.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+      .signers([bettor])
+      .rpc();
+    await program.methods.freezeMarket().accounts({ authority: authority.publicKey, market }).signers([authority]).rpc();
+    await program.methods
+      .resolveMarket(0, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
+    const m = await program.account.betMarket.fetch(market);
+    assert.strictEqual(m.claimDeadline.toNumber(), m.resolvedAt.toNumber() + 7 * 86_400);
+
+    // Nothing can be swept while the window is open; the winner still claims.
+    try {
+      await program.methods
+        .sweepUnclaimed()
+        .accounts({ caller: authority.publicKey, market, authority: authority.publicKey, treasury })
+        .signers([authority])
+        .rpc();
+      assert.fail("sweep must wait for the claim deadline");
+    } catch (e) {
+      assert.match(String(e), /ClaimWindowOpen/);
+    }
+    await program.methods.claimWinnings().accounts({ user: bettor.publicKey, market, ticket }).rpc();
+  });
+
+  it("closes tickets orphaned by a closed market", async () => {
+    const authority = Keypair.generate();
+    const [backer, doubter] = [Keypair.generate(), Keypair.generate()];
+    for (const kp of [authority, backer, doubter]) {
+      await requestAirdrop(provider.connection, kp.publicKey, 2);
+    }
+
+    const [authorityMeta] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority_meta"), authority.publicKey.toBuffer()],
+      program.programId,
+    );
+    const [market] = PublicKey.findProgramAddressSync(
+      [Buffer.from("market"), authority.publicKey.toBuffer(), cycleSeed(0)],
+      program.programId,
+    );
+    const [treasury] = PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+    const ticketOf = (kp) =>
+      PublicKey.findProgramAddressSync([Buffer.from("ticket"), market.toBuffer(), kp.publicKey.toBuffer()], program.programId)[0];
+
+    await program.methods
+      .initAuthorityMeta()
+      .accounts({ authority: authority.publicKey, authorityMeta, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    await program.methods
+      .initializeMarket(new anchor.BN(0), "Orphans", "YES", "NO", null, null)
+      .accounts({ authority: authority.publicKey, authorityMeta, market, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+    const stake = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+    for (const [kp, side] of [[backer, 0], [doubter, 1]]) {
+      const ticket = ticketOf(kp);
+      await program.methods
+        .createTicket(side, null)
+        .accounts({ user: kp.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([kp])
+        .rpc();
+      await program.methods
+        .placeBet(stake)
+        .accounts({ user: kp.publicKey, market, ticket, systemProgram: SystemProgram.programId })
+        .signers([kp])
+        .rpc();
+    }
+    await program.methods.freezeMarket().accounts({ authority: authority.publicKey, market }).signers([authority]).rpc();
+    await program.methods
+      .resolveMarket(0, Array(32).fill(0), 10_000, { manual: {} })
+      .accounts({ resolver: authority.publicKey, market })
+      .signers([authority])
+      .rpc();
+    await program.methods.claimWinnings().accounts({ user: backer.publicKey, market, ticket: ticketOf(backer) }).rpc();
+    await program.methods
+      .withdrawFees()
+      .accounts({ caller: authority.publicKey, authority: authority.publicKey, market, treasury })
+      .signers([authority])
+      .rpc();
+    // The losing ticket is never closed before the market goes away.
+    await program.methods
+      .closeMarket()
+      .accounts({ caller: authority.publicKey, authority: authority.publicKey, market, treasury })
+      .signers([authority])
+      .rpc();
+
+    const before = await provider.connection.getBalance(doubter.publicKey);
+    await program.methods
+      .closeOrphanTicket()
+      .accounts({ user: doubter.publicKey, market, ticket: ticketOf(doubter) })
+      .rpc();
+    assert.ok((await provider.connection.getBalance(doubter.publicKey)) > before, "ticket rent returned");
+    assert.strictEqual(await provider.connection.getAccountInfo(ticketOf(doubter)), null);
   });
 });